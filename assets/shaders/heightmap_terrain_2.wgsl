@@ -17,6 +17,9 @@ struct HeightmapMaterial {
     river_position: vec4<f32>,
     noise_config: vec4<f32>,
     debug_options: vec4<f32>,         
+    caustic_params: vec4<f32>, // intensity, scale, speed, depth_fade
+    water_params: vec4<f32>,   // wave_amplitude, wave_frequency, wave_speed, wave_steepness
+    water_misc: vec4<f32>,     // water_surface_y, unused, unused, time
 };
 
 @group(2) @binding(101)
@@ -61,6 +64,14 @@ fn get_noise_seed() -> f32 { return heightmap_material.noise_config.w; }
 fn get_show_mask() -> f32 { return heightmap_material.debug_options.x; }
 fn get_mask_mode() -> f32 { return heightmap_material.debug_options.z; }
 
+fn get_caustic_intensity() -> f32 { return heightmap_material.caustic_params.x; }
+fn get_caustic_scale() -> f32 { return heightmap_material.caustic_params.y; }
+fn get_caustic_speed() -> f32 { return heightmap_material.caustic_params.z; }
+fn get_caustic_depth_fade() -> f32 { return heightmap_material.caustic_params.w; }
+
+fn get_water_level() -> f32 { return heightmap_material.water_misc.x; }
+fn get_water_time() -> f32 { return heightmap_material.water_misc.w; }
+
 fn get_cell_step() -> f32 {
     let s = heightmap_material.debug_options.y;
     return select(2.0, s, s > 0.0001);
@@ -451,6 +462,127 @@ fn calculate_terrain_normal(position: vec2<f32>) -> vec3<f32> {
     return normalize(cross(tangent_z, tangent_x));
 }
 
+// ===== Caustics (ported from caustic_floor.wgsl) =====
+
+fn mod289_vec2(x: vec2<f32>) -> vec2<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn mod289_vec3(x: vec3<f32>) -> vec3<f32> {
+    return x - floor(x * (1.0 / 289.0)) * 289.0;
+}
+
+fn permute3(x: vec3<f32>) -> vec3<f32> {
+    return mod289_vec3(((x * 34.0) + 1.0) * x);
+}
+
+fn simplex2d(v: vec2<f32>) -> f32 {
+    let C = vec4<f32>(0.211324865405187, 0.366025403784439, -0.577350269189626, 0.024390243902439);
+
+    var i = floor(v + dot(v, vec2<f32>(C.y)));
+    let x0 = v - i + dot(i, vec2<f32>(C.x));
+
+    var i1: vec2<f32>;
+    if (x0.x > x0.y) {
+        i1 = vec2<f32>(1.0, 0.0);
+    } else {
+        i1 = vec2<f32>(0.0, 1.0);
+    }
+
+    let x1 = x0.xy + C.xx - i1;
+    let x2 = x0.xy + C.zz;
+
+    i = mod289_vec2(i);
+    let p = permute3(permute3(i.y + vec3<f32>(0.0, i1.y, 1.0)) + i.x + vec3<f32>(0.0, i1.x, 1.0));
+
+    var m = max(0.5 - vec3<f32>(dot(x0, x0), dot(x1, x1), dot(x2, x2)), vec3<f32>(0.0));
+    m = m * m;
+    m = m * m;
+
+    let x = 2.0 * fract(p * C.w) - 1.0;
+    let h = abs(x) - 0.5;
+    let ox = floor(x + 0.5);
+    let a0 = x - ox;
+
+    m = m * (1.79284291400159 - 0.85373472095314 * (a0 * a0 + h * h));
+
+    let g = vec3<f32>(
+        a0.x * x0.x + h.x * x0.y,
+        a0.y * x1.x + h.y * x1.y,
+        a0.z * x2.x + h.z * x2.y
+    );
+
+    return 130.0 * dot(m, g);
+}
+
+// Approximate water surface height, same frequency scaling as masked_river_water.wgsl
+fn get_water_surface_height(pos: vec2<f32>, time: f32) -> f32 {
+    let wave_amplitude = heightmap_material.water_params.x;
+    let wave_frequency = heightmap_material.water_params.y * 0.1;
+    let wave_speed = heightmap_material.water_params.z;
+
+    let animated_pos = pos * wave_frequency;
+    let time_offset1 = vec2<f32>(time * wave_speed * 0.3, time * wave_speed * 0.2);
+    let time_offset2 = vec2<f32>(time * wave_speed * 0.1, time * wave_speed * 0.4);
+
+    let noise1 = simplex2d(animated_pos + time_offset1);
+    let noise2 = simplex2d(animated_pos * 0.7 + time_offset2);
+
+    return (noise1 * 0.7 + noise2 * 0.3) * wave_amplitude;
+}
+
+fn calculate_caustics(world_pos: vec3<f32>, time: f32) -> f32 {
+    let caustic_scale = get_caustic_scale();
+    let caustic_speed = get_caustic_speed();
+
+    // Depth below the current water level
+    let depth = max(0.0, get_water_level() - world_pos.y);
+
+    // Surface slope at this point drives how strongly the light is focused
+    let sample_offset = 0.5 * caustic_scale;
+    let base_pos = world_pos.xz;
+    let h_right = get_water_surface_height(base_pos + vec2<f32>(sample_offset, 0.0), time);
+    let h_left = get_water_surface_height(base_pos + vec2<f32>(-sample_offset, 0.0), time);
+    let h_forward = get_water_surface_height(base_pos + vec2<f32>(0.0, sample_offset), time);
+    let h_back = get_water_surface_height(base_pos + vec2<f32>(0.0, -sample_offset), time);
+
+    let dx = (h_right - h_left) / (2.0 * sample_offset);
+    let dz = (h_forward - h_back) / (2.0 * sample_offset);
+    let surface_normal = normalize(vec3<f32>(-dx, 1.0, -dz));
+
+    // Simplified air -> water refraction of a vertical light ray
+    let eta = 1.0 / 1.33;
+    let cos_i = surface_normal.y;
+    let sin_t_squared = eta * eta * (1.0 - cos_i * cos_i);
+    if (sin_t_squared > 1.0) {
+        return 0.0;
+    }
+    let cos_t = sqrt(1.0 - sin_t_squared);
+    let refraction_strength = abs(cos_i - cos_t);
+
+    // Caustic patterns are scaled down so the slider range reads well at terrain scale
+    let caustic_time = time * caustic_speed;
+    let caustic_pos = world_pos.xz * caustic_scale * 0.05;
+
+    let c1 = simplex2d(caustic_pos + vec2<f32>(caustic_time * 0.3, caustic_time * 0.2));
+    let c2 = simplex2d(caustic_pos * 1.3 + vec2<f32>(caustic_time * -0.2, caustic_time * 0.4));
+    let c3 = simplex2d(caustic_pos * 0.8 + vec2<f32>(caustic_time * 0.1, caustic_time * -0.3));
+    let c4 = simplex2d(caustic_pos * 2.1 + vec2<f32>(caustic_time * 0.15, caustic_time * 0.25));
+    let c5 = simplex2d(caustic_pos * 1.7 + vec2<f32>(caustic_time * -0.1, caustic_time * 0.2));
+
+    let primary_caustic = (c1 + c2 * 0.7 + c3 * 0.5) * 0.4 + 0.5;
+    let secondary_caustic = (c4 + c5 * 0.6) * 0.3 + 0.5;
+    let combined_caustic = primary_caustic * 0.7 + secondary_caustic * 0.3;
+
+    let focused_caustic = pow(clamp(combined_caustic, 0.0, 1.0), 2.0 - refraction_strength);
+    let sharp_caustic = pow(focused_caustic, 3.0) * 2.0;
+    let final_caustic = mix(focused_caustic, sharp_caustic, 0.3);
+
+    let fade_factor = exp(-depth * get_caustic_depth_fade());
+
+    return final_caustic * fade_factor;
+}
+
 @vertex
 fn vertex(vertex: Vertex) -> VertexOutput {
     var out: VertexOutput;
@@ -612,6 +744,14 @@ fn fragment(
         pbr_input.material.metallic = 0.1;
     }
     
+    // Caustics on the river bed, only where the terrain lies under the river water
+    let in_river_corridor = calculate_river_effects(in.world_position.xz).river_modification < 0.0;
+    if (in_river_corridor && height < get_water_level() && get_caustic_intensity() > 0.0) {
+        let caustic_effect = calculate_caustics(in.world_position.xyz, get_water_time());
+        let caustic_color = vec3<f32>(0.9, 1.0, 0.95);
+        pbr_input.material.emissive = vec4<f32>(caustic_color * caustic_effect * get_caustic_intensity(), 0.0);
+    }
+    
     let final_result = apply_pbr_lighting(pbr_input);
     
     var out: FragmentOutput;
@@ -13,11 +13,15 @@ struct WaterMaterial {
     river_position: vec4<f32>,
     terrain_params: vec4<f32>,
     debug_options: vec4<f32>, // x=show_mask, y=margin_step_world, z=bank_fill_ratio
+    ripple_params: vec4<f32>, // x,y=origin_xz, z=world_size (0 = off), w=height_scale
 };
 
 @group(2) @binding(100)
 var<uniform> water_material: WaterMaterial;
 
+@group(2) @binding(101)
+var ripple_texture: texture_2d<f32>;
+
 // Accessors - match terrain shader exactly
 fn get_river_width() -> f32 { return water_material.river_params.x; }
 fn get_bank_slope_distance() -> f32 { return water_material.river_params.y; }
@@ -33,6 +37,51 @@ fn get_foam_intensity() -> f32 { return water_material.misc_params.y; }
 fn get_foam_cutoff() -> f32 { return water_material.misc_params.z; }
 fn get_time() -> f32 { return water_material.misc_params.w; }
 
+// Interactive ripples from the water_ripples compute pass (bilinear, texture is unfilterable)
+fn get_ripple_height(pos: vec2<f32>) -> f32 {
+    let world_size = water_material.ripple_params.z;
+    if (world_size <= 0.0) {
+        return 0.0;
+    }
+
+    let dims = vec2<i32>(textureDimensions(ripple_texture));
+    let uv = (pos - water_material.ripple_params.xy) / world_size + 0.5;
+    if (uv.x <= 0.0 || uv.y <= 0.0 || uv.x >= 1.0 || uv.y >= 1.0) {
+        return 0.0;
+    }
+
+    let texel = uv * vec2<f32>(dims) - 0.5;
+    let base = floor(texel);
+    let f = texel - base;
+    let i = vec2<i32>(base);
+    let max_i = dims - vec2<i32>(1);
+
+    let h00 = textureLoad(ripple_texture, clamp(i, vec2<i32>(0), max_i), 0).x;
+    let h10 = textureLoad(ripple_texture, clamp(i + vec2<i32>(1, 0), vec2<i32>(0), max_i), 0).x;
+    let h01 = textureLoad(ripple_texture, clamp(i + vec2<i32>(0, 1), vec2<i32>(0), max_i), 0).x;
+    let h11 = textureLoad(ripple_texture, clamp(i + vec2<i32>(1, 1), vec2<i32>(0), max_i), 0).x;
+
+    return mix(mix(h00, h10, f.x), mix(h01, h11, f.x), f.y) * water_material.ripple_params.w;
+}
+
+// Ripple height gradient (dh/dx, dh/dz), used to tilt the wave normal
+fn get_ripple_gradient(pos: vec2<f32>) -> vec2<f32> {
+    let world_size = water_material.ripple_params.z;
+    if (world_size <= 0.0) {
+        return vec2<f32>(0.0);
+    }
+
+    let eps = world_size / f32(textureDimensions(ripple_texture).x);
+    let dx = get_ripple_height(pos + vec2<f32>(eps, 0.0)) - get_ripple_height(pos - vec2<f32>(eps, 0.0));
+    let dz = get_ripple_height(pos + vec2<f32>(0.0, eps)) - get_ripple_height(pos - vec2<f32>(0.0, eps));
+    return vec2<f32>(dx, dz) / (2.0 * eps);
+}
+
+fn add_ripple_normal(normal: vec3<f32>, pos: vec2<f32>) -> vec3<f32> {
+    let g = get_ripple_gradient(pos);
+    return normalize(normal - vec3<f32>(g.x, 0.0, g.y));
+}
+
 fn mod289_vec2(x: vec2<f32>) -> vec2<f32> {
     return x - floor(x * (1.0 / 289.0)) * 289.0;
 }
@@ -300,7 +349,7 @@ fn vertex(vertex: Vertex) -> VertexOutput {
     let wave_height = wave_displacement.y;
 
     var displaced = world_pos4;
-    displaced.y = world_pos4.y + wave_height;
+    displaced.y = world_pos4.y + wave_height + get_ripple_height(world_pos4.xz);
     displaced.x = wave_displacement.x;
     // displaced.z = world_pos4.z + wave_displacement.z;
 
@@ -311,6 +360,7 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 
     out.position = position_world_to_clip(displaced.xyz);
     out.world_position = displaced;
+    // Ripple normals are added per pixel in the fragment stage
     out.world_normal = get_noise_wave_normal(world_pos4.xz, t);
     out.uv = vertex.uv;
 #ifdef VERTEX_TANGENTS
@@ -360,6 +410,10 @@ fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> Fragment
 
     var pbr_input = pbr_input_from_standard_material(in, is_front);
 
+    // Ripples are finer than the water mesh, so they only tilt the per-pixel normal
+    let surface_normal = add_ripple_normal(normalize(in.world_normal), in.world_position.xz);
+    pbr_input.N = surface_normal;
+
     let t = get_time();
     
     // SOPHISTICATED WAVE HEIGHT CALCULATION from simplex_water.wgsl
@@ -390,7 +444,7 @@ fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> Fragment
 
     // SOPHISTICATED FRESNEL EFFECT from simplex_water.wgsl
     let view_dir = normalize(view.world_position.xyz - in.world_position.xyz);
-    let fresnel_factor = fresnel_water(view_dir, in.world_normal);
+    let fresnel_factor = fresnel_water(view_dir, surface_normal);
     
     // Apply Fresnel effect to make water more reflective
     let reflection_color = vec3<f32>(0.8, 0.9, 1.0);
@@ -398,6 +452,24 @@ fn fragment(in: VertexOutput, @builtin(front_facing) is_front: bool) -> Fragment
     
     pbr_input.material.base_color = vec4<f32>(final_col, alpha);
 
+    // Seen from below: the sky only shows through Snell's window, everything
+    // outside it is total internal reflection of the water underneath
+    if (!is_front) {
+        let under_normal = -surface_normal;
+        pbr_input.N = under_normal;
+        let cos_i = max(dot(view_dir, under_normal), 0.0);
+        // Critical angle for water -> air (n = 1.33): cos = sqrt(1 - 1 / 1.33^2) ~ 0.66
+        let window = smoothstep(0.62, 0.70, cos_i);
+        let transmitted = window * (1.0 - fresnel(cos_i, 0.02));
+
+        let reflected_depths = vec3<f32>(0.04, 0.14, 0.2);
+        let sky_color = vec3<f32>(0.75, 0.88, 1.0);
+        let under_col = mix(reflected_depths, color, 0.25);
+
+        pbr_input.material.base_color = vec4<f32>(under_col * (1.0 - transmitted), mix(0.95, alpha * 0.6, transmitted));
+        pbr_input.material.emissive = vec4<f32>(sky_color * transmitted * 2.0, 0.0);
+    }
+
     let lit = apply_pbr_lighting(pbr_input);
     var out: FragmentOutput;
     out.color = lit;
@@ -0,0 +1,57 @@
+// Underwater post-process: absorption tint, wobble distortion and light shafts
+// from the surface. Runs after tonemapping on cameras with `UnderwaterCamera`.
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct UnderwaterSettings {
+    tint: vec4<f32>,       // rgb = water colour, a = tint strength
+    absorption: vec4<f32>, // rgb = absorption per unit depth, a = camera depth
+    effects: vec4<f32>,    // x = distortion, y = light shafts, z = time, w = look up (-1..1)
+    misc: vec4<f32>,       // x = submerged (0..1)
+};
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+@group(0) @binding(2) var<uniform> settings: UnderwaterSettings;
+
+fn hash11(x: f32) -> f32 {
+    return fract(sin(x * 127.1) * 43758.5453);
+}
+
+fn value_noise(x: f32) -> f32 {
+    let i = floor(x);
+    let f = fract(x);
+    let u = f * f * (3.0 - 2.0 * f);
+    return mix(hash11(i), hash11(i + 1.0), u);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let submerged = settings.misc.x;
+    let t = settings.effects.z;
+    let depth = settings.absorption.a;
+
+    // Refraction wobble
+    let wobble = vec2<f32>(
+        sin(in.uv.y * 38.0 + t * 2.1) + sin(in.uv.y * 17.0 - t * 1.3) * 0.5,
+        cos(in.uv.x * 33.0 + t * 1.7) + cos(in.uv.x * 21.0 + t * 0.9) * 0.5,
+    ) * settings.effects.x * 0.003 * submerged;
+    let uv = clamp(in.uv + wobble, vec2<f32>(0.001), vec2<f32>(0.999));
+    let original = textureSample(screen_texture, texture_sampler, in.uv).rgb;
+    var color = textureSample(screen_texture, texture_sampler, uv).rgb;
+
+    // Light loses red first, then green, the deeper the camera is
+    let transmittance = exp(-settings.absorption.rgb * (depth + 2.0));
+    color = color * transmittance;
+    color = mix(color, settings.tint.rgb, settings.tint.a);
+
+    // Slanted shafts coming down from the surface; strongest near the top of
+    // the screen and when looking up, fading out with depth
+    let slant = in.uv.x + (1.0 - in.uv.y) * 0.25;
+    let shafts = value_noise(slant * 24.0 + t * 0.35) * value_noise(slant * 57.0 - t * 0.6);
+    let shaft_mask = pow(shafts, 3.0) * pow(1.0 - in.uv.y, 1.5);
+    let look_up = clamp(settings.effects.w * 0.5 + 0.5, 0.0, 1.0);
+    let shaft_strength = settings.effects.y * shaft_mask * (0.3 + 0.7 * look_up) * exp(-depth * 0.04);
+    color += settings.tint.rgb * 2.5 * shaft_strength + vec3<f32>(shaft_strength * 0.3);
+
+    return vec4<f32>(mix(original, color, submerged), 1.0);
+}
@@ -0,0 +1,59 @@
+// Wave-equation ripple simulation, centred on the camera.
+// Each texel stores .x = current height, .y = previous height.
+
+struct RippleStep {
+    sim_params: vec4<f32>, // propagation, damping, impulse_count, unused
+    shift: vec4<f32>,      // texel shift since the last frame (xy)
+    impulses: array<vec4<f32>, 32>, // texel_x, texel_y, radius_texels, strength
+};
+
+@group(0) @binding(0) var input: texture_storage_2d<rgba32float, read>;
+@group(0) @binding(1) var output: texture_storage_2d<rgba32float, write>;
+@group(0) @binding(2) var<storage, read> ripple: RippleStep;
+
+fn load_state(p: vec2<i32>) -> vec2<f32> {
+    let size = vec2<i32>(textureDimensions(input));
+    if (p.x < 0 || p.y < 0 || p.x >= size.x || p.y >= size.y) {
+        return vec2<f32>(0.0);
+    }
+    return textureLoad(input, p).xy;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn simulate(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<i32>(textureDimensions(output));
+    let p = vec2<i32>(id.xy);
+    if (p.x >= size.x || p.y >= size.y) {
+        return;
+    }
+
+    // Read from where this texel was before the area scrolled with the camera
+    let src = p + vec2<i32>(ripple.shift.xy);
+    let state = load_state(src);
+
+    let left = load_state(src + vec2<i32>(-1, 0)).x;
+    let right = load_state(src + vec2<i32>(1, 0)).x;
+    let down = load_state(src + vec2<i32>(0, -1)).x;
+    let up = load_state(src + vec2<i32>(0, 1)).x;
+    let laplacian = left + right + up + down - 4.0 * state.x;
+
+    let propagation = ripple.sim_params.x;
+    let damping = ripple.sim_params.y;
+    var next = (2.0 * state.x - state.y + propagation * laplacian) * damping;
+
+    // Inject impulses with a smooth cosine falloff
+    let count = i32(ripple.sim_params.z);
+    for (var i = 0; i < count; i = i + 1) {
+        let impulse = ripple.impulses[i];
+        let d = distance(vec2<f32>(p), impulse.xy);
+        if (d < impulse.z) {
+            next -= impulse.w * (0.5 + 0.5 * cos(3.14159265359 * d / impulse.z));
+        }
+    }
+
+    // Absorb waves near the border so they don't bounce back off the edge
+    let edge = min(min(p.x, p.y), min(size.x - 1 - p.x, size.y - 1 - p.y));
+    let edge_fade = clamp(f32(edge) / 8.0, 0.0, 1.0);
+
+    textureStore(output, p, vec4<f32>(next * edge_fade, state.x * edge_fade, 0.0, 1.0));
+}
@@ -0,0 +1,3 @@
+pub mod ron_asset;
+
+pub use ron_asset::*;
@@ -6,6 +6,7 @@ use bevy::prelude::*;
 use bevy_blendy_cameras::{FlyCameraController, OrbitCameraController};
 use bevy_egui::{egui, EguiContexts};
 
+use crate::game::GameState;
 use crate::heightmap_material::{GpuHeightmapRenderConfig, GpuHeightmapTerrain};
 
 // ===== TURBULENCE TRAIT =====
@@ -156,7 +157,7 @@ impl Plugin for FlyByPlugin {
             .add_event::<RestoreCameraPosition>()
             .init_resource::<FlybyState>()
             .add_systems(Update, (
-                flyby_ui_system,
+                flyby_ui_system.run_if(in_state(GameState::Editor)),
                 camera_event_handler_system,
                 animate_river_raid_camera,
                 debug_path_system,
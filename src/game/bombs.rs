@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+
+use crate::game::{collider_transform, spawn_explosion, Collider, CollisionLayer, CollisionSet, DamageEvent, FlightSet, GameState, InRun, PlayerFlight, RiverCourse, Velocity};
+use crate::heightmap_material::{GpuHeightmapRenderConfig, TerrainSampler, WaterImpulse};
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Falls under gravity and goes off when it reaches the ground or the water
+#[derive(Component)]
+pub struct Bomb {
+    pub velocity: Vec3,
+}
+
+/// Lets an entity drop bombs. Input sets `release`, `release_bombs` clears it.
+#[derive(Component, Default)]
+pub struct BombBay {
+    /// Seconds until the next bomb can drop
+    pub cooldown: f32,
+    pub release: bool,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct BombConfig {
+    pub gravity: f32,
+    /// Seconds between drops
+    pub reload: f32,
+    /// Damage at the centre of the blast, falling off to nothing at `blast_radius`
+    pub damage: f32,
+    pub blast_radius: f32,
+    /// Depth a bomb landing in the water pushes the surface down by
+    pub splash_strength: f32,
+    /// Time step used to trace the impact prediction
+    pub prediction_step: f32,
+    /// Give up predicting after this many seconds of fall
+    pub max_fall_time: f32,
+}
+
+impl Default for BombConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 40.0,
+            reload: 0.75,
+            damage: 6.0,
+            blast_radius: 14.0,
+            splash_strength: 2.0,
+            prediction_step: 0.05,
+            max_fall_time: 8.0,
+        }
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+/// Bomb drops and detonations; input should run before this
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BombSet;
+
+pub struct BombPlugin;
+
+impl Plugin for BombPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BombConfig>()
+            .add_systems(Update, (
+                attach_bomb_bay,
+                release_bombs,
+                fall_bombs,
+            ).chain().in_set(BombSet).after(FlightSet).before(CollisionSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Height of whatever a bomb would hit at `xz`: the terrain, or the water above it
+pub fn bomb_surface_height(course: &TerrainSampler, water_level: f32, xz: Vec2) -> f32 {
+    course.height(xz).max(water_level)
+}
+
+/// Where a bomb released at `position` with `velocity` would land,
+/// traced in steps of `BombConfig::prediction_step`
+pub fn predict_bomb_impact(
+    config: &BombConfig,
+    course: &TerrainSampler,
+    water_level: f32,
+    mut position: Vec3,
+    mut velocity: Vec3,
+) -> Option<Vec3> {
+    let dt = config.prediction_step.max(0.005);
+    let mut time = 0.0;
+    while time < config.max_fall_time {
+        velocity.y -= config.gravity * dt;
+        let next = position + velocity * dt;
+        let surface = bomb_surface_height(course, water_level, next.xz());
+        if next.y <= surface {
+            // Land between the two samples, in proportion to how far each was from the surface
+            let above = position.y - bomb_surface_height(course, water_level, position.xz());
+            let below = surface - next.y;
+            let t = if above + below > 0.0 { above / (above + below) } else { 1.0 };
+            let hit = position.lerp(next, t.clamp(0.0, 1.0));
+            return Some(hit.with_y(bomb_surface_height(course, water_level, hit.xz())));
+        }
+        position = next;
+        time += dt;
+    }
+    None
+}
+
+fn attach_bomb_bay(mut commands: Commands, players: Query<Entity, (With<PlayerFlight>, Without<BombBay>)>) {
+    for entity in players.iter() {
+        commands.entity(entity).insert(BombBay::default());
+    }
+}
+
+fn release_bombs(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<BombConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bays: Query<(&mut BombBay, &Transform, Option<&Velocity>)>,
+) {
+    for (mut bay, transform, velocity) in bays.iter_mut() {
+        bay.cooldown = (bay.cooldown - time.delta_secs()).max(0.0);
+        if !std::mem::take(&mut bay.release) || bay.cooldown > 0.0 {
+            continue;
+        }
+        bay.cooldown = config.reload;
+
+        // Bombs leave with the plane's velocity and fall away under it
+        let velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.0);
+        commands.spawn((
+            Name::new("Bomb"),
+            Mesh3d(meshes.add(Capsule3d::new(0.35, 1.2))),
+            MeshMaterial3d(materials.add(Color::srgb(0.15, 0.16, 0.14))),
+            Transform::from_translation(transform.translation - Vec3::Y * 1.5),
+            Bomb { velocity },
+            StateScoped(InRun),
+        ));
+    }
+}
+
+fn fall_bombs(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<BombConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bombs: Query<(Entity, &mut Bomb, &mut Transform)>,
+    targets: Query<(Entity, &Transform, &Collider, Option<&ChildOf>), Without<Bomb>>,
+    parents: Query<&Transform, Without<Bomb>>,
+    mut damage: EventWriter<DamageEvent>,
+    mut splashes: EventWriter<WaterImpulse>,
+) {
+    let dt = time.delta_secs();
+    let water_level = render_config.water_level_offset;
+
+    for (entity, mut bomb, mut transform) in bombs.iter_mut() {
+        bomb.velocity.y -= config.gravity * dt;
+        transform.translation += bomb.velocity * dt;
+        // Capsules are built along Y; point the nose down the arc
+        if let Some(direction) = bomb.velocity.try_normalize() {
+            transform.rotation = Quat::from_rotation_arc(Vec3::NEG_Y, direction);
+        }
+
+        let surface = bomb_surface_height(&course.0, water_level, transform.translation.xz());
+        if transform.translation.y > surface {
+            continue;
+        }
+
+        let impact = transform.translation.with_y(surface);
+        for (target, target_transform, collider, child_of) in targets.iter() {
+            // Bombs hurt whatever the player's bullets can
+            if !CollisionLayer::PlayerBullet.collides_with(collider.layer) {
+                continue;
+            }
+            let parent = child_of.and_then(|child_of| parents.get(child_of.parent()).ok());
+            let target_position = collider_transform(target_transform, parent).translation;
+            let distance = (target_position.distance(impact) - collider.shape.bounding_radius()).max(0.0);
+            if distance < config.blast_radius {
+                damage.write(DamageEvent {
+                    target,
+                    amount: config.damage * (1.0 - distance / config.blast_radius),
+                    source: None,
+                });
+            }
+        }
+
+        if course.0.height(impact.xz()) < water_level {
+            splashes.write(WaterImpulse {
+                pos: impact,
+                radius: config.blast_radius,
+                strength: config.splash_strength,
+            });
+        }
+        spawn_explosion(&mut commands, &mut meshes, &mut materials, impact, config.blast_radius * 0.5, None);
+        commands.entity(entity).despawn();
+    }
+}
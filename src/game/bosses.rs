@@ -0,0 +1,582 @@
+use bevy::asset::LoadedFolder;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::data::RonAssetPlugin;
+use crate::game::{collider_transform, lead_direction, CameraShake, Collider, CollisionLayer, CollisionSet, ContactDamage, DamageEvent, Destroyed, EnemyBody, EnemySet, GameState, InRun, Libraries, PlayerFlight, RiverCourse, Velocity};
+use crate::heightmap_material::{GpuHeightmapRenderConfig, TerrainSampler};
+use crate::rendering::bullet::{spawn_bullet, BulletAssets};
+
+const BOSS_FOLDER: &str = "bosses";
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// How one attack sends out its shots
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AttackPattern {
+    /// A single shot led onto the player
+    Aimed,
+    /// `count` shots fanned across `arc` degrees, centred on the lead
+    Spread { count: u32, arc: f32 },
+    /// `count` shots spaced evenly all the way round, tilted up by `elevation` degrees
+    Ring { count: u32, elevation: f32 },
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct BossAttack {
+    pub pattern: AttackPattern,
+    /// Weapon name, see `assets/weapons`. Only its projectile is used; the rate comes from `interval`.
+    pub weapon: String,
+    /// Muzzle position in the boss's own frame, nose along +Z
+    #[serde(default)]
+    pub mount: [f32; 3],
+    /// Seconds between volleys
+    pub interval: f32,
+}
+
+/// Target on the boss that takes damage of its own and passes it on, multiplied
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct WeakPointDef {
+    pub name: String,
+    /// Position in the boss's own frame, nose along +Z
+    pub offset: [f32; 3],
+    pub radius: f32,
+    pub health: f32,
+    /// Boss damage per point of damage dealt to the weak point
+    pub multiplier: f32,
+    #[serde(default)]
+    pub points: u32,
+    /// sRGB
+    pub color: [f32; 3],
+}
+
+/// Where the boss keeps itself relative to the player
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct BossMovement {
+    /// Distance ahead of the player it holds station at
+    pub ahead: f32,
+    /// Side to side weave across the river
+    #[serde(default)]
+    pub sway: f32,
+    /// Seconds for one full weave
+    #[serde(default = "default_sway_period")]
+    pub sway_period: f32,
+    /// Height above the ground or water
+    #[serde(default)]
+    pub height: f32,
+    /// How fast it closes on its station and follows the weave
+    pub speed: f32,
+}
+
+fn default_sway_period() -> f32 {
+    6.0
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct BossPhase {
+    pub name: String,
+    /// Fraction of health at which this phase gives way to the next; ignored on the last phase
+    pub until: f32,
+    pub movement: BossMovement,
+    #[serde(default)]
+    pub attacks: Vec<BossAttack>,
+    /// Spawned when the phase starts; any left over from the phase before are removed
+    #[serde(default)]
+    pub weak_points: Vec<WeakPointDef>,
+}
+
+/// Boss loaded from `assets/bosses/*.boss.ron`
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct BossDef {
+    pub name: String,
+    pub body: EnemyBody,
+    /// sRGB
+    pub color: [f32; 3],
+    /// Half size of the hull's box collider
+    pub half_extents: [f32; 3],
+    pub health: f32,
+    /// Boss damage per point of damage dealt to the hull
+    #[serde(default = "default_armour")]
+    pub armour: f32,
+    pub points: u32,
+    /// Only attacks while the player is this close
+    #[serde(default = "default_range")]
+    pub range: f32,
+    pub phases: Vec<BossPhase>,
+}
+
+fn default_armour() -> f32 {
+    1.0
+}
+
+fn default_range() -> f32 {
+    400.0
+}
+
+/* ------------------------------- Events ------------------------------- */
+
+/// A boss has started a phase, including its first when it appears
+#[derive(Event, Clone, Debug)]
+pub struct BossPhaseChanged {
+    pub boss: Entity,
+    pub name: String,
+    pub phase: usize,
+    pub phase_name: String,
+    pub phases: usize,
+}
+
+/// A boss ran out of health. Sent alongside its `Destroyed`.
+#[derive(Event, Clone, Debug)]
+pub struct BossDefeated {
+    pub name: String,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Keeps its own health rather than `Health`, so hull hits can be scaled by armour
+/// and weak point hits by their multiplier
+#[derive(Component, Clone, Debug)]
+pub struct Boss {
+    pub def: Handle<BossDef>,
+    pub name: String,
+    pub health: f32,
+    pub max_health: f32,
+    /// Index into the def's phases; `None` until the first phase starts
+    pub phase: Option<usize>,
+    /// Seconds since the current phase started
+    pub phase_time: f32,
+    /// Distance ahead of the player
+    pub offset: f32,
+    /// Offset from the river's centre line
+    pub lateral: f32,
+    /// Seconds until each attack of the current phase fires
+    cooldowns: Vec<f32>,
+}
+
+impl Boss {
+    /// Boss appearing `offset` ahead of the player, with its health scaled by `health_scale`
+    pub fn new(handle: Handle<BossDef>, def: &BossDef, offset: f32, health_scale: f32) -> Self {
+        let health = def.health * health_scale;
+        Self {
+            def: handle,
+            name: def.name.clone(),
+            health,
+            max_health: health,
+            phase: None,
+            phase_time: 0.0,
+            offset,
+            lateral: 0.0,
+            cooldowns: Vec::new(),
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        (self.health / self.max_health.max(f32::EPSILON)).clamp(0.0, 1.0)
+    }
+}
+
+/// Child of a boss with a collider of its own
+#[derive(Component, Clone, Debug)]
+pub struct WeakPoint {
+    pub boss: Entity,
+    pub health: f32,
+    pub multiplier: f32,
+    pub points: u32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+/// All bosses found in the boss folder
+#[derive(Resource)]
+pub struct BossLibrary {
+    folder: Handle<LoadedFolder>,
+}
+
+impl BossLibrary {
+    pub fn find<'a>(
+        &self,
+        name: &str,
+        folders: &Assets<LoadedFolder>,
+        defs: &'a Assets<BossDef>,
+    ) -> Option<(Handle<BossDef>, &'a BossDef)> {
+        folders
+            .get(&self.folder)?
+            .handles
+            .iter()
+            .filter_map(|handle| handle.clone().try_typed::<BossDef>().ok())
+            .filter_map(|handle| defs.get(&handle).map(|def| (handle, def)))
+            .find(|(_, def)| def.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct BossConfig {
+    /// Camera shake added when a boss changes phase
+    pub phase_shake: f32,
+    /// Camera shake added when a boss is destroyed
+    pub defeat_shake: f32,
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        Self {
+            phase_shake: 0.5,
+            defeat_shake: 1.0,
+        }
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<BossDef>::new(&["boss.ron"]))
+            .init_resource::<BossConfig>()
+            .add_event::<BossPhaseChanged>()
+            .add_event::<BossDefeated>()
+            .add_systems(Startup, load_boss_defs)
+            .add_systems(Update, (
+                move_bosses,
+                fire_boss_attacks,
+            ).chain().after(EnemySet).before(CollisionSet).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (
+                damage_bosses,
+                advance_boss_phases,
+                shake_camera_on_boss_events,
+            ).chain().after(CollisionSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn load_boss_defs(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(BossLibrary {
+        folder: asset_server.load_folder(BOSS_FOLDER),
+    });
+}
+
+/// Spawns a boss at `position`. Its first phase, and the weak points that come with it,
+/// start on the next update.
+pub fn spawn_boss(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    def: &BossDef,
+    boss: Boss,
+    position: Vec3,
+) -> Entity {
+    let mesh = match def.body {
+        EnemyBody::Box(size) => meshes.add(Cuboid::from_size(Vec3::from_array(size))),
+        EnemyBody::Sphere(radius) => meshes.add(Sphere::new(radius)),
+        // Lying down, nose along +Z
+        EnemyBody::Capsule { radius, length } => meshes.add(
+            Capsule3d::new(radius, length)
+                .mesh()
+                .build()
+                .rotated_by(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+        ),
+    };
+    let [r, g, b] = def.color;
+    commands
+        .spawn((
+            Name::new(def.name.clone()),
+            Transform::from_translation(position),
+            Mesh3d(mesh),
+            MeshMaterial3d(materials.add(Color::srgb(r, g, b))),
+            boss,
+            Velocity::default(),
+            Collider::cuboid(Vec3::from_array(def.half_extents), CollisionLayer::Enemy),
+            ContactDamage(100.0),
+            StateScoped(InRun),
+        ))
+        .id()
+}
+
+/// World position of a boss `along` the river, `lateral` off the centre line and
+/// `height` above the ground or water
+pub fn boss_position(terrain: &TerrainSampler, water_level: f32, along: f32, lateral: f32, height: f32) -> Vec3 {
+    let xz = terrain.axis_point(along, terrain.meander(along) + lateral);
+    Vec3::new(xz.x, terrain.height(xz).max(water_level) + height, xz.y)
+}
+
+/// Holds station ahead of the player, weaving across the river and facing back down it
+fn move_bosses(
+    time: Res<Time>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    defs: Res<Assets<BossDef>>,
+    players: Query<&PlayerFlight>,
+    mut bosses: Query<(&mut Boss, &mut Transform, &mut Velocity)>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    // Stay put while the player is waiting to respawn
+    let Ok(flight) = players.single() else {
+        return;
+    };
+    let terrain = &course.0;
+    let water_level = render_config.water_level_offset;
+
+    for (mut boss, mut transform, mut velocity) in bosses.iter_mut() {
+        let Some(def) = defs.get(&boss.def) else {
+            continue;
+        };
+        let Some(phase) = def.phases.get(boss.phase.unwrap_or(0)) else {
+            continue;
+        };
+        let movement = phase.movement;
+        boss.phase_time += dt;
+
+        let step = movement.speed * dt;
+        boss.offset += (movement.ahead - boss.offset).clamp(-step, step);
+        let along = flight.along + boss.offset;
+
+        // Keep the hull inside the channel however wide the weave
+        let room = (terrain.river_width_at(along) * 0.5 - def.half_extents[0]).max(0.0);
+        let weave = (boss.phase_time * std::f32::consts::TAU / movement.sway_period.max(0.1)).sin();
+        let wanted = (weave * movement.sway).clamp(-room, room);
+        boss.lateral += (wanted - boss.lateral).clamp(-step, step);
+
+        let position = boss_position(terrain, water_level, along, boss.lateral, movement.height);
+        velocity.0 = (position - transform.translation) / dt;
+        transform.translation = position;
+
+        // Nose is +Z; point it back down the river at the player
+        let downstream = terrain.river_center(along - 1.0) - terrain.river_center(along + 1.0);
+        if let Some(facing) = Vec3::new(downstream.x, 0.0, downstream.y).try_normalize() {
+            transform.look_to(-facing, Vec3::Y);
+        }
+    }
+}
+
+fn fire_boss_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    course: Res<RiverCourse>,
+    libraries: Libraries,
+    mut bullet_assets: ResMut<BulletAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    players: Query<(&Transform, &Velocity), With<PlayerFlight>>,
+    mut bosses: Query<(&mut Boss, &Transform, &Velocity), Without<PlayerFlight>>,
+) {
+    let dt = time.delta_secs();
+    let Ok((player, player_velocity)) = players.single() else {
+        return;
+    };
+    let player_along = course.0.distance_along(player.translation.xz());
+
+    for (mut boss, transform, velocity) in bosses.iter_mut() {
+        let Some(def) = libraries.boss_defs.get(&boss.def) else {
+            continue;
+        };
+        let Some(phase) = boss.phase.and_then(|index| def.phases.get(index)) else {
+            continue;
+        };
+        // Hold fire until the player is in range and still downstream
+        let boss_along = course.0.distance_along(transform.translation.xz());
+        if player.translation.distance(transform.translation) > def.range || player_along > boss_along {
+            continue;
+        }
+
+        for (attack, cooldown) in phase.attacks.iter().zip(boss.cooldowns.iter_mut()) {
+            *cooldown -= dt;
+            if *cooldown > 0.0 {
+                continue;
+            }
+            *cooldown += attack.interval.max(0.1);
+            let Some((weapon_handle, weapon)) = libraries.weapon(&attack.weapon) else {
+                warn!("Boss '{}' names unknown weapon '{}'", def.name, attack.weapon);
+                continue;
+            };
+
+            let muzzle = transform.transform_point(Vec3::from_array(attack.mount));
+            let offset = player.translation - muzzle;
+            let lead = lead_direction(offset, player_velocity.0 - velocity.0, weapon)
+                .unwrap_or(offset.normalize_or(Vec3::Z));
+            let directions: Vec<Vec3> = match attack.pattern {
+                AttackPattern::Aimed => vec![lead],
+                AttackPattern::Spread { count, arc } => {
+                    let count = count.max(1);
+                    (0..count)
+                        .map(|i| {
+                            let fan = if count > 1 { i as f32 / (count - 1) as f32 - 0.5 } else { 0.0 };
+                            Quat::from_rotation_y((fan * arc).to_radians()) * lead
+                        })
+                        .collect()
+                }
+                AttackPattern::Ring { count, elevation } => {
+                    let count = count.max(1);
+                    let tilted = Quat::from_rotation_x(-elevation.to_radians()) * Vec3::Z;
+                    (0..count)
+                        .map(|i| Quat::from_rotation_y(i as f32 / count as f32 * std::f32::consts::TAU) * tilted)
+                        .collect()
+                }
+            };
+
+            let look = bullet_assets.look(weapon_handle.id(), weapon, &mut meshes, &mut materials);
+            for direction in directions {
+                spawn_bullet(
+                    &mut commands,
+                    look.clone(),
+                    weapon,
+                    CollisionLayer::EnemyBullet,
+                    muzzle,
+                    velocity.0 + direction * weapon.projectile_speed,
+                );
+            }
+        }
+    }
+}
+
+/// Passes hull and weak point damage on to the boss, and blows up weak points that run out.
+/// Ramming doesn't count, or the player could trade a life for the boss.
+fn damage_bosses(
+    mut commands: Commands,
+    mut events: EventReader<DamageEvent>,
+    defs: Res<Assets<BossDef>>,
+    mut bosses: Query<(&mut Boss, &Transform)>,
+    mut weak_points: Query<(&mut WeakPoint, &Transform, &Collider)>,
+    mut destroyed: EventWriter<Destroyed>,
+) {
+    for event in events.read() {
+        if event.source == Some(CollisionLayer::Player) {
+            continue;
+        }
+        if let Ok((mut boss, _)) = bosses.get_mut(event.target) {
+            let armour = defs.get(&boss.def).map_or(1.0, |def| def.armour);
+            boss.health = (boss.health - event.amount * armour).min(boss.max_health);
+            continue;
+        }
+
+        let Ok((mut weak_point, transform, collider)) = weak_points.get_mut(event.target) else {
+            continue;
+        };
+        if weak_point.health <= 0.0 {
+            continue;
+        }
+        weak_point.health -= event.amount;
+        let Ok((mut boss, boss_transform)) = bosses.get_mut(weak_point.boss) else {
+            continue;
+        };
+        boss.health = (boss.health - event.amount * weak_point.multiplier).min(boss.max_health);
+
+        if weak_point.health <= 0.0 {
+            destroyed.write(Destroyed {
+                position: collider_transform(transform, Some(boss_transform)).translation,
+                layer: CollisionLayer::Enemy,
+                radius: collider.shape.bounding_radius(),
+                points: weak_point.points,
+            });
+            commands.entity(event.target).despawn();
+        }
+    }
+}
+
+/// Starts the first phase, moves on as health drops past each threshold and
+/// destroys the boss once it runs out
+fn advance_boss_phases(
+    mut commands: Commands,
+    defs: Res<Assets<BossDef>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bosses: Query<(Entity, &mut Boss, &Transform, &Collider)>,
+    weak_points: Query<(Entity, &WeakPoint)>,
+    mut changed: EventWriter<BossPhaseChanged>,
+    mut defeated: EventWriter<BossDefeated>,
+    mut destroyed: EventWriter<Destroyed>,
+) {
+    for (entity, mut boss, transform, collider) in bosses.iter_mut() {
+        let Some(def) = defs.get(&boss.def) else {
+            continue;
+        };
+
+        if boss.health <= 0.0 {
+            destroyed.write(Destroyed {
+                position: transform.translation,
+                layer: CollisionLayer::Enemy,
+                radius: collider.shape.bounding_radius(),
+                points: def.points,
+            });
+            defeated.write(BossDefeated {
+                name: boss.name.clone(),
+            });
+            info!("🏆 {} destroyed", boss.name);
+            // Weak points are children and go with it
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        // A big enough hit can skip a phase entirely
+        let fraction = boss.fraction();
+        let mut next = boss.phase.unwrap_or(0);
+        while next + 1 < def.phases.len() && fraction <= def.phases[next].until {
+            next += 1;
+        }
+        if boss.phase == Some(next) {
+            continue;
+        }
+        let Some(phase) = def.phases.get(next) else {
+            continue;
+        };
+
+        for (weak_entity, weak_point) in weak_points.iter() {
+            if weak_point.boss == entity {
+                commands.entity(weak_entity).despawn();
+            }
+        }
+        for weak_def in &phase.weak_points {
+            let [r, g, b] = weak_def.color;
+            commands.spawn((
+                Name::new(weak_def.name.clone()),
+                Transform::from_translation(Vec3::from_array(weak_def.offset)),
+                Mesh3d(meshes.add(Sphere::new(weak_def.radius))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::srgb(r, g, b),
+                    emissive: LinearRgba::rgb(r, g, b) * 2.0,
+                    ..default()
+                })),
+                WeakPoint {
+                    boss: entity,
+                    health: weak_def.health,
+                    multiplier: weak_def.multiplier,
+                    points: weak_def.points,
+                },
+                Collider::sphere(weak_def.radius, CollisionLayer::Enemy),
+                ContactDamage(100.0),
+                ChildOf(entity),
+            ));
+        }
+
+        boss.phase = Some(next);
+        boss.phase_time = 0.0;
+        boss.cooldowns = phase.attacks.iter().map(|attack| attack.interval.max(0.1)).collect();
+        info!("☠ {} phase {}: {}", boss.name, next + 1, phase.name);
+        changed.write(BossPhaseChanged {
+            boss: entity,
+            name: boss.name.clone(),
+            phase: next,
+            phase_name: phase.name.clone(),
+            phases: def.phases.len(),
+        });
+    }
+}
+
+fn shake_camera_on_boss_events(
+    config: Res<BossConfig>,
+    mut changed: EventReader<BossPhaseChanged>,
+    mut defeated: EventReader<BossDefeated>,
+    mut shake: ResMut<CameraShake>,
+) {
+    // The first phase is the boss arriving, not a transition
+    for event in changed.read() {
+        if event.phase > 0 {
+            shake.add(config.phase_shake);
+        }
+    }
+    for _ in defeated.read() {
+        shake.add(config.defeat_shake);
+    }
+}
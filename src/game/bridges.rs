@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+
+use crate::game::{Collider, CollisionLayer, ContactDamage, FlightSet, GameState, Health, InRun, PlayerFlight, PlayerRespawned, Points, RiverCourse};
+use crate::heightmap_material::GpuHeightmapRenderConfig;
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Span across the river. Blocks the plane until it is shot down.
+#[derive(Component)]
+pub struct Bridge {
+    /// Distance along the river axis the bridge was placed at
+    pub along: f32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct BridgeConfig {
+    pub spacing: f32,
+    /// Deck height above the water
+    pub deck_height: f32,
+    pub deck_thickness: f32,
+    pub deck_width: f32,
+    /// Length beyond the water channel on each side
+    pub overhang: f32,
+    pub health: f32,
+    pub points: u32,
+    pub spawn_ahead: f32,
+    pub despawn_behind: f32,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            spacing: 900.0,
+            deck_height: 10.0,
+            deck_thickness: 8.0,
+            deck_width: 6.0,
+            overhang: 30.0,
+            health: 5.0,
+            points: 500,
+            spawn_ahead: 500.0,
+            despawn_behind: 80.0,
+        }
+    }
+}
+
+/// Where the next bridge goes, in distance along the river axis
+#[derive(Resource, Default)]
+struct NextBridge {
+    along: f32,
+}
+
+#[derive(Resource)]
+struct BridgeAssets {
+    material: Handle<StandardMaterial>,
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct BridgePlugin;
+
+impl Plugin for BridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BridgeConfig>()
+            .init_resource::<NextBridge>()
+            .add_systems(Startup, setup_bridge_assets)
+            .add_systems(OnEnter(InRun), reset_bridges)
+            .add_systems(Update, (rewind_bridges, stream_bridges).chain().after(FlightSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn setup_bridge_assets(mut commands: Commands, mut materials: ResMut<Assets<StandardMaterial>>) {
+    commands.insert_resource(BridgeAssets {
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.45, 0.42, 0.38),
+            perceptual_roughness: 0.9,
+            ..default()
+        }),
+    });
+}
+
+fn reset_bridges(mut next: ResMut<NextBridge>, config: Res<BridgeConfig>) {
+    next.along = config.spacing;
+}
+
+/// Clears the bridges and starts placing them again from the first one past the respawn
+/// point, which is the bridge the checkpoint was taken at
+fn rewind_bridges(
+    mut commands: Commands,
+    mut respawned: EventReader<PlayerRespawned>,
+    config: Res<BridgeConfig>,
+    mut next: ResMut<NextBridge>,
+    bridges: Query<Entity, With<Bridge>>,
+) {
+    let Some(respawn) = respawned.read().last() else {
+        return;
+    };
+    for entity in bridges.iter() {
+        commands.entity(entity).despawn();
+    }
+    // Checkpoints are measured from where the bridge was destroyed, so allow a little slack
+    let spacing = config.spacing.max(1.0);
+    next.along = ((respawn.along + 1.0) / spacing).ceil() * spacing;
+}
+
+/// Places bridges across the river ahead of the player and clears the ones left behind
+fn stream_bridges(
+    mut commands: Commands,
+    config: Res<BridgeConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    assets: Res<BridgeAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut next: ResMut<NextBridge>,
+    players: Query<&PlayerFlight>,
+    bridges: Query<(Entity, &Bridge)>,
+) {
+    let Ok(flight) = players.single() else {
+        return;
+    };
+    let course = &course.0;
+
+    while next.along < flight.along + config.spawn_ahead {
+        let along = next.along;
+        let center = course.river_center(along);
+        // Lay the span square to the channel, not to the river axis
+        let tangent = (course.river_center(along + 1.0) - course.river_center(along - 1.0)).normalize_or_zero();
+        let across = Vec2::new(-tangent.y, tangent.x);
+        let length = course.river_width_at(along) + config.overhang * 2.0;
+        let y = render_config.water_level_offset + config.deck_height;
+
+        let half_extents = Vec3::new(length * 0.5, config.deck_thickness * 0.5, config.deck_width * 0.5);
+        commands.spawn((
+            Name::new("Bridge"),
+            Mesh3d(meshes.add(Cuboid::from_size(half_extents * 2.0))),
+            MeshMaterial3d(assets.material.clone()),
+            Transform::from_xyz(center.x, y, center.y)
+                .with_rotation(Quat::from_rotation_y((-across.y).atan2(across.x))),
+            Bridge { along },
+            Collider::cuboid(half_extents, CollisionLayer::Bridge),
+            Health::new(config.health),
+            ContactDamage(100.0),
+            Points(config.points),
+            StateScoped(InRun),
+        ));
+
+        next.along += config.spacing;
+    }
+
+    for (entity, bridge) in bridges.iter() {
+        if bridge.along < flight.along - config.despawn_behind {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::game::{FlightSet, GameState, InRun, Invulnerable};
+use crate::heightmap_material::{GpuHeightmapRenderConfig, WaterImpulse};
+
+/// Depth an explosion on the water pushes the surface down by
+const EXPLOSION_SPLASH: f32 = 1.5;
+
+/* ------------------------------- Events ------------------------------- */
+
+/// `amount` of damage dealt to `target`, by a collision or by gameplay code.
+/// Negative amounts heal, up to `Health::max`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    /// Layer of the collider that dealt it; `None` for gameplay damage such as bombs
+    pub source: Option<CollisionLayer>,
+}
+
+/// An entity ran out of health. Sent just before it is despawned;
+/// explosions, score and game flow hook in here.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Destroyed {
+    pub position: Vec3,
+    pub layer: CollisionLayer,
+    /// Bounding radius, used to size the explosion
+    pub radius: f32,
+    pub points: u32,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CollisionLayer {
+    Player,
+    PlayerBullet,
+    Enemy,
+    EnemyBullet,
+    Bridge,
+    FuelDepot,
+}
+
+impl CollisionLayer {
+    pub fn collides_with(self, other: CollisionLayer) -> bool {
+        use CollisionLayer::*;
+        matches!(
+            (self, other),
+            (Player, Enemy | EnemyBullet | Bridge)
+                | (Enemy | EnemyBullet | Bridge, Player)
+                | (PlayerBullet, Enemy | Bridge | FuelDepot)
+                | (Enemy | Bridge | FuelDepot, PlayerBullet)
+        )
+    }
+
+    /// Layers that move fast or matter most; only these look for contacts
+    fn is_active(self) -> bool {
+        matches!(self, CollisionLayer::Player | CollisionLayer::PlayerBullet)
+    }
+}
+
+/// Shapes are in world units and ignore the transform's scale
+#[derive(Clone, Copy, Debug)]
+pub enum ColliderShape {
+    Sphere { radius: f32 },
+    /// Oriented by the transform's rotation
+    Box { half_extents: Vec3 },
+}
+
+impl ColliderShape {
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            ColliderShape::Sphere { radius } => *radius,
+            ColliderShape::Box { half_extents } => half_extents.length(),
+        }
+    }
+}
+
+/// Read from `Transform`. A collider on a child entity is also moved by its
+/// parent's `Transform`, one level deep; see `collider_transform`.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Collider {
+    pub shape: ColliderShape,
+    pub layer: CollisionLayer,
+}
+
+impl Collider {
+    pub fn sphere(radius: f32, layer: CollisionLayer) -> Self {
+        Self {
+            shape: ColliderShape::Sphere { radius },
+            layer,
+        }
+    }
+
+    pub fn cuboid(half_extents: Vec3, layer: CollisionLayer) -> Self {
+        Self {
+            shape: ColliderShape::Box { half_extents },
+            layer,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// Damage dealt to whatever this entity collides with
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ContactDamage(pub f32);
+
+/// Removed on the first hit, e.g. bullets
+#[derive(Component)]
+pub struct DespawnOnHit;
+
+/// Score awarded when destroyed
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Points(pub u32);
+
+#[derive(Component)]
+struct Explosion {
+    age: f32,
+    lifetime: f32,
+    radius: f32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+/// Uniform grid over XZ, rebuilt every frame
+#[derive(Resource)]
+pub struct SpatialHash {
+    pub cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self {
+            cell_size: 16.0,
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl SpatialHash {
+    fn cell_range(&self, position: Vec3, radius: f32) -> (IVec2, IVec2) {
+        let min = ((position.xz() - Vec2::splat(radius)) / self.cell_size).floor().as_ivec2();
+        let max = ((position.xz() + Vec2::splat(radius)) / self.cell_size).floor().as_ivec2();
+        (min, max)
+    }
+
+    pub fn clear(&mut self) {
+        // Drop cells that stayed empty, keep the rest's allocations for the next frame
+        self.cells.retain(|_, entities| !entities.is_empty());
+        for entities in self.cells.values_mut() {
+            entities.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec3, radius: f32) {
+        let (min, max) = self.cell_range(position, radius);
+        for x in min.x..=max.x {
+            for z in min.y..=max.y {
+                self.cells.entry(IVec2::new(x, z)).or_default().push(entity);
+            }
+        }
+    }
+
+    /// Entities in every cell touched by a sphere at `position`. May contain duplicates.
+    pub fn query(&self, position: Vec3, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let (min, max) = self.cell_range(position, radius);
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |z| IVec2::new(x, z)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+/// Where a collider is in the world: its own transform, placed by its parent's if it has one.
+/// Read straight from `Transform` rather than `GlobalTransform`, which lags a frame behind.
+pub fn collider_transform(transform: &Transform, parent: Option<&Transform>) -> Transform {
+    parent.map_or(*transform, |parent| parent.mul_transform(*transform))
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollisionSet;
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialHash>()
+            .add_event::<DamageEvent>()
+            .add_event::<Destroyed>()
+            .add_systems(Update, (
+                rebuild_spatial_hash,
+                detect_collisions,
+                apply_damage,
+                despawn_destroyed,
+            ).chain().in_set(CollisionSet).after(FlightSet).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (spawn_explosions, animate_explosions).after(CollisionSet).run_if(in_state(InRun)));
+    }
+}
+
+fn rebuild_spatial_hash(
+    mut hash: ResMut<SpatialHash>,
+    colliders: Query<(Entity, &Transform, &Collider, Option<&ChildOf>)>,
+    parents: Query<&Transform>,
+) {
+    hash.clear();
+    for (entity, transform, collider, child_of) in colliders.iter() {
+        let parent = child_of.and_then(|child_of| parents.get(child_of.parent()).ok());
+        let transform = collider_transform(transform, parent);
+        hash.insert(entity, transform.translation, collider.shape.bounding_radius());
+    }
+}
+
+fn detect_collisions(
+    mut commands: Commands,
+    hash: Res<SpatialHash>,
+    colliders: Query<(
+        Entity,
+        &Transform,
+        &Collider,
+        Option<&ContactDamage>,
+        Has<DespawnOnHit>,
+        Option<&ChildOf>,
+        Has<Invulnerable>,
+    )>,
+    parents: Query<&Transform>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    let mut tested = HashSet::new();
+    let mut spent = HashSet::new();
+    let world = |transform: &Transform, child_of: Option<&ChildOf>| {
+        collider_transform(transform, child_of.and_then(|child_of| parents.get(child_of.parent()).ok()))
+    };
+
+    for (entity, transform, collider, _, _, child_of, _) in colliders.iter() {
+        if !collider.layer.is_active() {
+            continue;
+        }
+        let transform = world(transform, child_of);
+        let position = transform.translation;
+        for other in hash.query(position, collider.shape.bounding_radius()) {
+            if other == entity
+                || spent.contains(&entity)
+                || spent.contains(&other)
+                || !tested.insert((entity.min(other), entity.max(other)))
+            {
+                continue;
+            }
+            let Ok((_, other_transform, other_collider, _, _, other_child_of, _)) = colliders.get(other) else {
+                continue;
+            };
+            let other_transform = world(other_transform, other_child_of);
+            if !collider.layer.collides_with(other_collider.layer)
+                || !shapes_overlap(&transform, &collider.shape, &other_transform, &other_collider.shape)
+            {
+                continue;
+            }
+
+            for (hitter, target) in [(entity, other), (other, entity)] {
+                let Ok((_, _, hitter_collider, contact, despawn_on_hit, _, invulnerable)) = colliders.get(hitter) else {
+                    continue;
+                };
+                // Invulnerable colliders deal no contact damage
+                if invulnerable {
+                    continue;
+                }
+                // Bullets only get to hit one thing
+                if despawn_on_hit {
+                    spent.insert(hitter);
+                }
+                if let Some(contact) = contact {
+                    damage.write(DamageEvent {
+                        target,
+                        amount: contact.0,
+                        source: Some(hitter_collider.layer),
+                    });
+                }
+                if despawn_on_hit {
+                    commands.entity(hitter).despawn();
+                }
+            }
+        }
+    }
+}
+
+fn apply_damage(mut events: EventReader<DamageEvent>, mut targets: Query<&mut Health, Without<Invulnerable>>) {
+    for event in events.read() {
+        if let Ok(mut health) = targets.get_mut(event.target) {
+            health.current = (health.current - event.amount).min(health.max);
+        }
+    }
+}
+
+fn despawn_destroyed(
+    mut commands: Commands,
+    dead: Query<(Entity, &Health, &Transform, Option<&Collider>, Option<&Points>, Option<&ChildOf>)>,
+    parents: Query<&Transform>,
+    mut destroyed: EventWriter<Destroyed>,
+) {
+    for (entity, health, transform, collider, points, child_of) in dead.iter() {
+        if !health.is_dead() {
+            continue;
+        }
+        let parent = child_of.and_then(|child_of| parents.get(child_of.parent()).ok());
+        destroyed.write(Destroyed {
+            position: collider_transform(transform, parent).translation,
+            layer: collider.map_or(CollisionLayer::Enemy, |c| c.layer),
+            radius: collider.map_or(2.0, |c| c.shape.bounding_radius()),
+            points: points.map_or(0, |p| p.0),
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_explosions(
+    mut commands: Commands,
+    mut events: EventReader<Destroyed>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let water_level = Some(render_config.water_level_offset);
+    for event in events.read() {
+        spawn_explosion(&mut commands, &mut meshes, &mut materials, event.position, event.radius, water_level);
+    }
+}
+
+/// Default explosion: an expanding, fading fireball about twice `radius` across.
+/// Going off at or below `water_level` also pushes the water down; `None` leaves the
+/// water alone, e.g. when the caller writes its own splash.
+pub fn spawn_explosion(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    position: Vec3,
+    radius: f32,
+    water_level: Option<f32>,
+) {
+    if water_level.is_some_and(|level| position.y <= level) {
+        commands.send_event(WaterImpulse {
+            pos: position,
+            radius: radius * 2.0,
+            strength: EXPLOSION_SPLASH,
+        });
+    }
+    commands.spawn((
+        Name::new("Explosion"),
+        Mesh3d(meshes.add(Sphere::new(1.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(1.0, 0.6, 0.1, 0.9),
+            emissive: LinearRgba::rgb(8.0, 3.0, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })),
+        Transform::from_translation(position).with_scale(Vec3::splat(0.1)),
+        Explosion {
+            age: 0.0,
+            lifetime: 0.6,
+            radius: radius * 2.0,
+        },
+        StateScoped(InRun),
+    ));
+}
+
+fn animate_explosions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut explosions: Query<(Entity, &mut Explosion, &mut Transform, &MeshMaterial3d<StandardMaterial>)>,
+) {
+    for (entity, mut explosion, mut transform, material) in explosions.iter_mut() {
+        explosion.age += time.delta_secs();
+        let t = (explosion.age / explosion.lifetime).clamp(0.0, 1.0);
+        if t >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let scale = EaseFunction::QuadraticOut.sample_clamped(t) * explosion.radius;
+        transform.scale = Vec3::splat(scale.max(0.1));
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.base_color.set_alpha(0.9 * (1.0 - t));
+        }
+    }
+}
+
+/* ---------------------------- Shape tests ----------------------------- */
+
+fn shapes_overlap(a: &Transform, shape_a: &ColliderShape, b: &Transform, shape_b: &ColliderShape) -> bool {
+    match (shape_a, shape_b) {
+        (ColliderShape::Sphere { radius: ra }, ColliderShape::Sphere { radius: rb }) => {
+            a.translation.distance_squared(b.translation) <= (ra + rb) * (ra + rb)
+        }
+        (ColliderShape::Sphere { radius }, ColliderShape::Box { half_extents }) => {
+            sphere_overlaps_box(a.translation, *radius, b, *half_extents)
+        }
+        (ColliderShape::Box { half_extents }, ColliderShape::Sphere { radius }) => {
+            sphere_overlaps_box(b.translation, *radius, a, *half_extents)
+        }
+        // Nothing that collides is box against box yet; bounding spheres are close enough
+        (ColliderShape::Box { .. }, ColliderShape::Box { .. }) => {
+            let r = shape_a.bounding_radius() + shape_b.bounding_radius();
+            a.translation.distance_squared(b.translation) <= r * r
+        }
+    }
+}
+
+fn sphere_overlaps_box(center: Vec3, radius: f32, box_transform: &Transform, half_extents: Vec3) -> bool {
+    let local = box_transform.rotation.inverse() * (center - box_transform.translation);
+    let closest = local.clamp(-half_extents, half_extents);
+    local.distance_squared(closest) <= radius * radius
+}
@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+
+use crate::game::{spawn_explosion, CollisionLayer, CollisionSet, Destroyed, FlightConfig, FlightSet, GameState, Invulnerable, PlayerFlight, RiverCourse};
+use crate::heightmap_material::GpuHeightmapRenderConfig;
+
+/* ------------------------------- Events ------------------------------- */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrashCause {
+    /// Flew into the ground
+    Terrain,
+    /// Left the water too low
+    RiverBank,
+    /// Shot down or rammed something
+    Collision,
+    OutOfFuel,
+}
+
+/// The player's plane was lost. Sent once per crash; lives and game flow listen for this.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlayerCrashed {
+    pub position: Vec3,
+    pub cause: CrashCause,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Marks the player as lost. Inserted by whatever detected the crash;
+/// the crash is reported and the plane removed once the marker lands.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Crashed {
+    pub cause: CrashCause,
+}
+
+/// Terrain and bank crashes are ignored until this runs out
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CrashGrace {
+    pub remaining: f32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct CrashConfig {
+    pub enabled: bool,
+    /// Seconds after spawning before terrain can kill the player
+    pub grace_period: f32,
+    /// Radius of the plane used against the ground
+    pub collision_radius: f32,
+    /// Outside the water the plane must stay at least this fraction of
+    /// `FlightConfig::clearance` above the ground. Flight holds the full clearance,
+    /// so only banks too steep for it to climb over in time are fatal.
+    pub bank_clearance_fraction: f32,
+}
+
+impl Default for CrashConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            grace_period: 2.0,
+            collision_radius: 2.0,
+            bank_clearance_fraction: 0.5,
+        }
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+/// Crash reporting; anything that inserts `Crashed` should run before this
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CrashSet;
+
+pub struct CrashPlugin;
+
+impl Plugin for CrashPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CrashConfig>()
+            .add_event::<PlayerCrashed>()
+            .configure_sets(Update, CrashSet.after(FlightSet).after(CollisionSet))
+            .add_systems(Update, (
+                start_crash_grace,
+                tick_crash_grace,
+                detect_terrain_crash,
+            ).chain().after(FlightSet).before(CrashSet).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, (
+                report_crashes,
+                report_collision_crash,
+            ).chain().in_set(CrashSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn start_crash_grace(
+    mut commands: Commands,
+    config: Res<CrashConfig>,
+    players: Query<Entity, Added<PlayerFlight>>,
+) {
+    for entity in players.iter() {
+        commands.entity(entity).insert(CrashGrace {
+            remaining: config.grace_period,
+        });
+    }
+}
+
+fn tick_crash_grace(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut players: Query<(Entity, &mut CrashGrace)>,
+) {
+    for (entity, mut grace) in players.iter_mut() {
+        grace.remaining -= time.delta_secs();
+        if grace.remaining <= 0.0 {
+            commands.entity(entity).remove::<CrashGrace>();
+        }
+    }
+}
+
+fn detect_terrain_crash(
+    mut commands: Commands,
+    config: Res<CrashConfig>,
+    flight_config: Res<FlightConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    players: Query<(Entity, &Transform), (With<PlayerFlight>, Without<CrashGrace>, Without<Crashed>, Without<Invulnerable>)>,
+) {
+    if !config.enabled {
+        return;
+    }
+    let water_level = render_config.water_level_offset;
+    let bank_clearance = flight_config.clearance * config.bank_clearance_fraction;
+
+    for (entity, transform) in players.iter() {
+        let position = transform.translation;
+        let ground = course.0.height(position.xz());
+        // The river mask: anywhere the terrain dips under the water line
+        let over_water = ground < water_level;
+
+        let cause = if position.y - config.collision_radius <= ground.max(water_level) {
+            Some(CrashCause::Terrain)
+        } else if !over_water && position.y - ground < bank_clearance {
+            Some(CrashCause::RiverBank)
+        } else {
+            None
+        };
+
+        if let Some(cause) = cause {
+            commands.entity(entity).insert(Crashed { cause });
+        }
+    }
+}
+
+fn report_crashes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    crashed: Query<(Entity, &Transform, &Crashed), Added<Crashed>>,
+    mut events: EventWriter<PlayerCrashed>,
+) {
+    for (entity, transform, crashed) in crashed.iter() {
+        events.write(PlayerCrashed {
+            position: transform.translation,
+            cause: crashed.cause,
+        });
+        spawn_explosion(&mut commands, &mut meshes, &mut materials, transform.translation, 4.0, Some(render_config.water_level_offset));
+        commands.entity(entity).despawn();
+    }
+}
+
+/// The collision pipeline already removed the plane and blew it up
+fn report_collision_crash(mut destroyed: EventReader<Destroyed>, mut events: EventWriter<PlayerCrashed>) {
+    for event in destroyed.read() {
+        if event.layer == CollisionLayer::Player {
+            events.write(PlayerCrashed {
+                position: event.position,
+                cause: CrashCause::Collision,
+            });
+        }
+    }
+}
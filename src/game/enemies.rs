@@ -0,0 +1,347 @@
+use bevy::asset::LoadedFolder;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::data::RonAssetPlugin;
+use crate::game::{CollisionLayer, CollisionSet, EnemyGunner, FlightSet, FormationMember, GameState, Libraries, PlayerFlight, RiverCourse, Steering, SteeringDef, TurretDef, Velocity, Weapon, WeaponSet};
+use crate::heightmap_material::GpuHeightmapRenderConfig;
+use crate::paths::{PathFollower, PathSet};
+
+const ENEMY_FOLDER: &str = "enemies";
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// How an archetype gets about. Distances are in world units, times in seconds.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MovementMode {
+    /// Hangs over the river and slides either side of its offset. `width` is
+    /// the fraction of the channel covered, `period` the time for one full sweep.
+    HoverStrafe { altitude: f32, width: f32, period: f32 },
+    /// Sails up or down the channel on the water surface
+    FollowRiver,
+    /// Drives back and forth along one bank, `setback` beyond the water's edge
+    PatrolBank { distance: f32, setback: f32 },
+    /// Flies straight down the river at the player
+    FastPass { altitude: f32 },
+    /// Stays where it was built, see `TurretPlugin`
+    Emplacement,
+}
+
+/// Stand-in shape drawn when an archetype has no model
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum EnemyBody {
+    Box([f32; 3]),
+    Sphere(f32),
+    Capsule { radius: f32, length: f32 },
+}
+
+/// Enemy type loaded from `assets/enemies/*.enemy.ron`
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct EnemyArchetype {
+    pub name: String,
+    /// glTF scene, e.g. `models/enemy/helicopter.gltf#Scene0`
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default = "default_model_scale")]
+    pub model_scale: f32,
+    pub body: EnemyBody,
+    /// sRGB
+    pub color: [f32; 3],
+    /// Collider radius
+    pub radius: f32,
+    pub health: f32,
+    pub points: u32,
+    pub speed: f32,
+    pub movement: MovementMode,
+    /// Steers freely instead of following `movement`
+    #[serde(default)]
+    pub steering: Option<SteeringDef>,
+    /// Swivelling mount for emplacements
+    #[serde(default)]
+    pub turret: Option<TurretDef>,
+    /// Name of a weapon in `assets/weapons`
+    #[serde(default)]
+    pub weapon: Option<String>,
+    /// Opens fire once the player is this close
+    #[serde(default)]
+    pub weapon_range: f32,
+    /// 1 fires exactly on the predicted intercept, 0 at `TargetingConfig::max_aim_error`
+    #[serde(default = "default_accuracy")]
+    pub accuracy: f32,
+    /// Seconds the player must be in sight before the first shot
+    #[serde(default = "default_reaction_time")]
+    pub reaction_time: f32,
+    /// Seconds of muzzle glow before each shot
+    #[serde(default = "default_telegraph")]
+    pub telegraph: f32,
+    /// Rolls into turns when flying a path; off for boats and ground vehicles
+    #[serde(default = "default_banks")]
+    pub banks: bool,
+}
+
+fn default_model_scale() -> f32 {
+    1.0
+}
+
+fn default_banks() -> bool {
+    true
+}
+
+fn default_accuracy() -> f32 {
+    0.7
+}
+
+fn default_reaction_time() -> f32 {
+    0.5
+}
+
+fn default_telegraph() -> f32 {
+    0.3
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+#[derive(Component, Clone, Debug)]
+pub struct EnemyKind(pub Handle<EnemyArchetype>);
+
+/// Where an enemy is in river coordinates; `move_enemies` places it from this
+/// unless it is on a path, in a formation or steering
+#[derive(Component, Clone, Copy, Debug)]
+pub struct EnemyMotion {
+    pub mode: MovementMode,
+    pub speed: f32,
+    /// Distance along the river axis the enemy was placed at
+    pub home: f32,
+    pub along: f32,
+    /// Offset from the river's centre line, positive to the right of the flight direction
+    pub offset: f32,
+    /// Which bank or which way along the river, -1 or 1
+    pub side: f32,
+    pub age: f32,
+}
+
+impl EnemyMotion {
+    pub fn new(mode: MovementMode, speed: f32, along: f32, offset: f32, side: f32) -> Self {
+        Self {
+            mode,
+            speed,
+            home: along,
+            along,
+            offset,
+            side,
+            age: 0.0,
+        }
+    }
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+/// All archetypes found in the enemy folder
+#[derive(Resource)]
+pub struct EnemyLibrary {
+    folder: Handle<LoadedFolder>,
+}
+
+impl EnemyLibrary {
+    /// Loaded archetypes, sorted by name
+    pub fn archetypes<'a>(
+        &self,
+        folders: &Assets<LoadedFolder>,
+        archetypes: &'a Assets<EnemyArchetype>,
+    ) -> Vec<(Handle<EnemyArchetype>, &'a EnemyArchetype)> {
+        let Some(folder) = folders.get(&self.folder) else {
+            return Vec::new();
+        };
+        let mut found: Vec<(Handle<EnemyArchetype>, &EnemyArchetype)> = folder
+            .handles
+            .iter()
+            .filter_map(|handle| handle.clone().try_typed::<EnemyArchetype>().ok())
+            .filter_map(|handle| archetypes.get(&handle).map(|archetype| (handle, archetype)))
+            .collect();
+        found.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        found
+    }
+
+    pub fn find<'a>(
+        &self,
+        name: &str,
+        folders: &Assets<LoadedFolder>,
+        archetypes: &'a Assets<EnemyArchetype>,
+    ) -> Option<(Handle<EnemyArchetype>, &'a EnemyArchetype)> {
+        self.archetypes(folders, archetypes)
+            .into_iter()
+            .find(|(_, archetype)| archetype.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct EnemyConfig {
+    /// How far behind the player enemies are removed
+    pub despawn_behind: f32,
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        Self {
+            despawn_behind: 80.0,
+        }
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+/// Enemy movement and aiming. Runs before weapons fire and collisions.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnemySet;
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<EnemyArchetype>::new(&["enemy.ron"]))
+            .init_resource::<EnemyConfig>()
+            .add_systems(Startup, load_enemy_archetypes)
+            .configure_sets(Update, EnemySet.after(PathSet))
+            .add_systems(Update, (
+                arm_enemies,
+                move_enemies,
+                track_path_enemies,
+                despawn_passed_enemies,
+            ).chain().in_set(EnemySet).after(FlightSet).before(WeaponSet).before(CollisionSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn load_enemy_archetypes(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(EnemyLibrary {
+        folder: asset_server.load_folder(ENEMY_FOLDER),
+    });
+}
+
+/// Hands each enemy the weapon its archetype names, once the weapon has loaded
+fn arm_enemies(
+    mut commands: Commands,
+    libraries: Libraries,
+    enemies: Query<(Entity, &EnemyKind), Without<Weapon>>,
+) {
+    for (entity, kind) in enemies.iter() {
+        let Some(name) = libraries.archetypes.get(&kind.0).and_then(|archetype| archetype.weapon.as_deref()) else {
+            continue;
+        };
+        if let Some((handle, def)) = libraries.weapon(name) {
+            commands
+                .entity(entity)
+                .insert((Weapon::new(handle, def, CollisionLayer::EnemyBullet), EnemyGunner::default()));
+        }
+    }
+}
+
+fn move_enemies(
+    time: Res<Time>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    mut enemies: Query<
+        (&mut EnemyMotion, &mut Transform, &mut Velocity),
+        (Without<PathFollower>, Without<FormationMember>, Without<Steering>),
+    >,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    let course = &course.0;
+    let water_level = render_config.water_level_offset;
+    let axis = course.river_axis();
+    // Toward the oncoming player
+    let upstream = Vec3::new(-axis.x, 0.0, -axis.y);
+
+    for (mut motion, mut transform, mut velocity) in enemies.iter_mut() {
+        motion.age += dt;
+        let half_width = course.river_width_at(motion.along) * 0.5;
+
+        let (position, facing) = match motion.mode {
+            MovementMode::HoverStrafe { altitude, width, period } => {
+                let sweep = (motion.age * std::f32::consts::TAU / period.max(0.1)).sin() * motion.side;
+                let centre = course.meander(motion.along) + motion.offset.clamp(-half_width, half_width);
+                let lateral = centre + sweep * width * half_width;
+                let xz = course.axis_point(motion.along, lateral);
+                let y = course.height(xz).max(water_level) + altitude;
+                (Vec3::new(xz.x, y, xz.y), Some(upstream))
+            }
+            MovementMode::FollowRiver => {
+                motion.along += motion.side * motion.speed * dt;
+                // Keep clear of the banks so the hull stays on open water
+                let offset = motion.offset.clamp(-half_width * 0.6, half_width * 0.6);
+                let xz = course.axis_point(motion.along, course.meander(motion.along) + offset);
+                (Vec3::new(xz.x, water_level, xz.y), None)
+            }
+            MovementMode::PatrolBank { distance, setback } => {
+                // Triangle wave around home so the tank turns round at each end
+                let span = distance.max(1.0);
+                let travelled = motion.age * motion.speed;
+                let phase = (travelled / span).rem_euclid(2.0);
+                let swing = if phase < 1.0 { phase } else { 2.0 - phase };
+                motion.along = motion.home + (swing - 0.5) * span;
+
+                // Out from the water's edge until the ground is dry
+                let centre = course.meander(motion.along);
+                let mut lateral = centre + motion.side * (half_width + setback);
+                let mut xz = course.axis_point(motion.along, lateral);
+                for _ in 0..16 {
+                    if course.height(xz) > water_level + 0.5 {
+                        break;
+                    }
+                    lateral += motion.side * 4.0;
+                    xz = course.axis_point(motion.along, lateral);
+                }
+                (Vec3::new(xz.x, course.height(xz), xz.y), None)
+            }
+            MovementMode::FastPass { altitude } => {
+                motion.along -= motion.speed * dt;
+                let xz = course.axis_point(motion.along, course.meander(motion.along) + motion.offset);
+                let y = course.height(xz).max(water_level) + altitude;
+                (Vec3::new(xz.x, y, xz.y), None)
+            }
+            MovementMode::Emplacement => (transform.translation, None),
+        };
+
+        // Skip the first frame so the spawn position doesn't read as a jump
+        velocity.0 = if motion.age > dt {
+            (position - transform.translation) / dt
+        } else {
+            Vec3::ZERO
+        };
+        transform.translation = position;
+
+        // Models face down their local +Z
+        let heading = facing.or_else(|| velocity.0.with_y(0.0).try_normalize());
+        if let Some(heading) = heading {
+            transform.rotation = Quat::from_rotation_arc(Vec3::Z, heading);
+        }
+    }
+}
+
+/// Keeps the river position of enemies that `move_enemies` doesn't place up to date for aiming and clean up
+fn track_path_enemies(
+    course: Res<RiverCourse>,
+    mut enemies: Query<(&mut EnemyMotion, &Transform), Or<(With<PathFollower>, With<FormationMember>, With<Steering>)>>,
+) {
+    for (mut motion, transform) in enemies.iter_mut() {
+        motion.along = course.0.distance_along(transform.translation.xz());
+    }
+}
+
+fn despawn_passed_enemies(
+    mut commands: Commands,
+    config: Res<EnemyConfig>,
+    players: Query<&PlayerFlight>,
+    enemies: Query<(Entity, &EnemyMotion)>,
+) {
+    let Ok(flight) = players.single() else {
+        return;
+    };
+    for (entity, motion) in enemies.iter() {
+        if motion.along < flight.along - config.despawn_behind {
+            commands.entity(entity).despawn();
+        }
+    }
+}
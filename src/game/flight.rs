@@ -0,0 +1,343 @@
+use bevy::prelude::*;
+use bevy_blendy_cameras::{FlyCameraController, OrbitCameraController};
+use rand::Rng;
+
+use crate::game::{GameState, InRun};
+use crate::heightmap_material::{
+    render_gpu_terrain, CompleteGpuHeightmapMaterial, CompleteMaskedRiverWaterMaterial,
+    GpuHeightmapConfigUI, GpuHeightmapRenderConfig, GpuHeightmapTerrain, GpuHeightmapWater,
+    TerrainSampler,
+};
+use crate::rendering::plane::Plane;
+
+/* ----------------------------- Components ----------------------------- */
+
+/// River Raid style flight state. The plane scrolls along the river axis on its own;
+/// the player only picks the sideways offset and the speed.
+#[derive(Component, Default)]
+pub struct PlayerFlight {
+    /// Distance travelled along the river axis. Set at spawn to start further down the river.
+    pub along: f32,
+    /// Sideways offset from the river axis
+    pub lateral: f32,
+    pub speed: f32,
+    pub lateral_velocity: f32,
+    /// Terrain (or water, whichever is higher) under the plane
+    pub ground_height: f32,
+}
+
+/// World space velocity, written by whatever moves the entity
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct Velocity(pub Vec3);
+
+/// Camera that trails the player while a run is active
+#[derive(Component)]
+pub struct ChaseCamera;
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct FlightConfig {
+    pub cruise_speed: f32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Speed change per second while throttling
+    pub throttle_rate: f32,
+    pub strafe_speed: f32,
+    /// How quickly sideways velocity follows the input
+    pub strafe_response: f32,
+    /// Extra room either side of the meander envelope
+    pub corridor_margin: f32,
+    /// Height kept above the ground or water
+    pub clearance: f32,
+    /// How far ahead the ground is sampled so the plane climbs before ridges
+    pub look_ahead: f32,
+    pub altitude_response: f32,
+    pub camera_distance: f32,
+    pub camera_height: f32,
+    pub camera_response: f32,
+    /// Largest camera wobble in degrees, at full shake
+    pub shake_angle: f32,
+    /// Shake lost per second
+    pub shake_decay: f32,
+}
+
+impl Default for FlightConfig {
+    fn default() -> Self {
+        Self {
+            cruise_speed: 40.0,
+            min_speed: 20.0,
+            max_speed: 80.0,
+            throttle_rate: 30.0,
+            strafe_speed: 35.0,
+            strafe_response: 6.0,
+            corridor_margin: 10.0,
+            clearance: 12.0,
+            look_ahead: 40.0,
+            altitude_response: 3.0,
+            camera_distance: 45.0,
+            camera_height: 18.0,
+            camera_response: 4.0,
+            shake_angle: 3.0,
+            shake_decay: 1.5,
+        }
+    }
+}
+
+/// CPU copy of the terrain the player flies over, rebuilt when the heightmap config changes
+#[derive(Resource, Clone)]
+pub struct RiverCourse(pub TerrainSampler);
+
+/// Shakes the chase camera. Anything can add to it; it wears off by itself.
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    /// 0 to 1; the wobble grows with its square so small amounts stay subtle
+    pub trauma: f32,
+}
+
+impl CameraShake {
+    pub fn add(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+}
+
+/// Player flight and anything that must see the plane's final transform for the frame
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlightSet;
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct FlightPlugin;
+
+impl Plugin for FlightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlightConfig>()
+            .init_resource::<CameraShake>()
+            .add_systems(Startup, init_river_course)
+            .add_systems(OnEnter(InRun), (spawn_river_world, attach_chase_camera))
+            .add_systems(OnExit(InRun), release_chase_camera)
+            .add_systems(Update, update_river_course)
+            .add_systems(Update, (
+                start_player_flight,
+                player_flight_input,
+                advance_player_flight,
+                recentre_river_world,
+            ).chain().in_set(FlightSet).after(update_river_course).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, chase_camera.after(FlightSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn init_river_course(mut commands: Commands, config: Res<GpuHeightmapConfigUI>) {
+    commands.insert_resource(RiverCourse(TerrainSampler::new(&config)));
+}
+
+fn update_river_course(config: Res<GpuHeightmapConfigUI>, mut course: ResMut<RiverCourse>) {
+    if config.is_changed() {
+        course.0 = TerrainSampler::new(&config);
+    }
+}
+
+/// Makes sure there is terrain to fly over when a run starts
+fn spawn_river_world(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut terrain_materials: ResMut<Assets<CompleteGpuHeightmapMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut water_materials: ResMut<Assets<CompleteMaskedRiverWaterMaterial>>,
+    mut height_config: ResMut<GpuHeightmapConfigUI>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    terrain_query: Query<Entity, With<GpuHeightmapTerrain>>,
+    water_query: Query<Entity, With<GpuHeightmapWater>>,
+) {
+    if !terrain_query.is_empty() {
+        return;
+    }
+    render_gpu_terrain(
+        &mut commands,
+        &mut meshes,
+        &mut terrain_materials,
+        &asset_server,
+        &mut water_materials,
+        &render_config,
+        &terrain_query,
+        &water_query,
+    );
+    // New materials start from their defaults; let the sync systems push the config
+    height_config.set_changed();
+}
+
+fn start_player_flight(
+    config: Res<FlightConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    mut query: Query<(&mut PlayerFlight, &mut Transform), Added<PlayerFlight>>,
+) {
+    for (mut flight, mut transform) in query.iter_mut() {
+        flight.lateral = course.0.meander(flight.along);
+        flight.speed = config.cruise_speed;
+        flight.lateral_velocity = 0.0;
+
+        let pos = course.0.axis_point(flight.along, flight.lateral);
+        flight.ground_height = course.0.height(pos).max(render_config.water_level_offset);
+        transform.translation = Vec3::new(pos.x, flight.ground_height + config.clearance, pos.y);
+    }
+}
+
+fn player_flight_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    config: Res<FlightConfig>,
+    mut query: Query<&mut PlayerFlight>,
+) {
+    let dt = time.delta_secs();
+    let pressed = |a: KeyCode, b: KeyCode| keyboard.pressed(a) || keyboard.pressed(b);
+
+    let mut throttle = 0.0;
+    if pressed(KeyCode::ArrowUp, KeyCode::KeyW) {
+        throttle += 1.0;
+    }
+    if pressed(KeyCode::ArrowDown, KeyCode::KeyS) {
+        throttle -= 1.0;
+    }
+
+    // Positive lateral is to the right of the flight direction
+    let mut steer = 0.0;
+    if pressed(KeyCode::ArrowLeft, KeyCode::KeyA) {
+        steer -= 1.0;
+    }
+    if pressed(KeyCode::ArrowRight, KeyCode::KeyD) {
+        steer += 1.0;
+    }
+
+    for mut flight in query.iter_mut() {
+        flight.speed = (flight.speed + throttle * config.throttle_rate * dt)
+            .clamp(config.min_speed, config.max_speed);
+
+        let target = steer * config.strafe_speed;
+        let blend = 1.0 - (-config.strafe_response * dt).exp();
+        flight.lateral_velocity = flight.lateral_velocity.lerp(target, blend);
+    }
+}
+
+fn advance_player_flight(
+    time: Res<Time>,
+    config: Res<FlightConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    mut query: Query<(&mut PlayerFlight, &mut Transform, &mut Velocity)>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    let course = &course.0;
+    let water_level = render_config.water_level_offset;
+    let corridor = course.meander_amplitude() * 1.5 + course.river_width_at(0.0) + config.corridor_margin;
+
+    for (mut flight, mut transform, mut velocity) in query.iter_mut() {
+        flight.along += flight.speed * dt;
+        flight.lateral = (flight.lateral + flight.lateral_velocity * dt).clamp(-corridor, corridor);
+        let pos = course.axis_point(flight.along, flight.lateral);
+
+        flight.ground_height = course.height(pos).max(water_level);
+        // Climb early for ridges coming up rather than only reacting to what is underneath
+        let ground_ahead = [0.25, 0.5, 1.0]
+            .into_iter()
+            .map(|f| course.axis_point(flight.along + config.look_ahead * f, flight.lateral))
+            .map(|p| course.height(p).max(water_level))
+            .fold(flight.ground_height, f32::max);
+
+        let target_y = ground_ahead + config.clearance;
+        let blend = 1.0 - (-config.altitude_response * dt).exp();
+        let y = transform.translation.y.lerp(target_y, blend);
+
+        let next = Vec3::new(pos.x, y, pos.y);
+        velocity.0 = (next - transform.translation) / dt;
+        transform.translation = next;
+
+        // The model's nose points down its local +Z
+        let heading = velocity.0.try_normalize().unwrap_or_else(|| {
+            let axis = course.river_axis();
+            Vec3::new(axis.x, 0.0, axis.y)
+        });
+        transform.rotation = Quat::from_rotation_arc(Vec3::Z, heading);
+    }
+}
+
+/// Keeps the terrain and water chunks under the plane. Snapped to whole vertex cells
+/// so the displaced grid doesn't shimmer as it moves.
+fn recentre_river_world(
+    render_config: Res<GpuHeightmapRenderConfig>,
+    plane: Query<&Transform, (With<Plane>, Without<GpuHeightmapTerrain>, Without<GpuHeightmapWater>)>,
+    mut terrain: Query<&mut Transform, (With<GpuHeightmapTerrain>, Without<GpuHeightmapWater>)>,
+    mut water: Query<&mut Transform, (With<GpuHeightmapWater>, Without<GpuHeightmapTerrain>)>,
+) {
+    let Ok(plane) = plane.single() else {
+        return;
+    };
+    let cell = render_config.chunk_size / (render_config.vertex_density.max(2) - 1) as f32;
+    let centre = (plane.translation.xz() / cell).round() * cell;
+
+    for mut transform in terrain.iter_mut().chain(water.iter_mut()) {
+        transform.translation.x = centre.x;
+        transform.translation.z = centre.y;
+    }
+}
+
+fn attach_chase_camera(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, Option<&mut OrbitCameraController>, Option<&mut FlyCameraController>), With<Camera3d>>,
+) {
+    for (entity, orbit, fly) in cameras.iter_mut() {
+        if let Some(mut orbit) = orbit {
+            orbit.is_enabled = false;
+        }
+        if let Some(mut fly) = fly {
+            fly.is_enabled = false;
+        }
+        commands.entity(entity).insert(ChaseCamera);
+    }
+}
+
+fn release_chase_camera(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, Option<&mut OrbitCameraController>), With<ChaseCamera>>,
+) {
+    for (entity, orbit) in cameras.iter_mut() {
+        if let Some(mut orbit) = orbit {
+            orbit.is_enabled = true;
+        }
+        commands.entity(entity).remove::<ChaseCamera>();
+    }
+}
+
+fn chase_camera(
+    time: Res<Time>,
+    config: Res<FlightConfig>,
+    course: Res<RiverCourse>,
+    mut shake: ResMut<CameraShake>,
+    plane: Query<&Transform, (With<PlayerFlight>, Without<ChaseCamera>)>,
+    mut cameras: Query<&mut Transform, With<ChaseCamera>>,
+) {
+    let Ok(plane) = plane.single() else {
+        return;
+    };
+    // Follow the scroll direction, not the plane's nose, so steering doesn't swing the view
+    let axis = course.0.river_axis();
+    let forward = Vec3::new(axis.x, 0.0, axis.y);
+    let target = plane.translation - forward * config.camera_distance + Vec3::Y * config.camera_height;
+    let blend = 1.0 - (-config.camera_response * time.delta_secs()).exp();
+
+    // Wobble the view rather than the position, so the follow stays smooth
+    let mut rng = rand::rng();
+    let wobble = config.shake_angle.to_radians() * shake.trauma * shake.trauma;
+    let mut jitter = || rng.random_range(-1.0..=1.0) * wobble;
+    let shaken = Quat::from_euler(EulerRot::YXZ, jitter(), jitter(), jitter());
+    shake.trauma = (shake.trauma - config.shake_decay * time.delta_secs()).max(0.0);
+
+    for mut transform in cameras.iter_mut() {
+        transform.translation = transform.translation.lerp(target, blend);
+        transform.look_at(plane.translation + forward * config.look_ahead, Vec3::Y);
+        transform.rotate_local(shaken);
+    }
+}
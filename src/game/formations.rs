@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::{EnemyArchetype, EnemyKind, EnemySet, GameState, InRun, PlayerFlight, RiverCourse, Velocity};
+use crate::heightmap_material::GpuHeightmapRenderConfig;
+use crate::paths::{CatmullRomPath, MeasuredPath, PathEnd, PathFollower, PathSet};
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// Slot layout, in the leader's frame
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FormationShape {
+    /// Leader at the point, the rest alternating back along both sides
+    V,
+    /// Side by side
+    Line,
+    /// A diagonal trailing back to one side, -1 (left) or 1 (right)
+    Echelon(f32),
+}
+
+impl FormationShape {
+    /// Offset of slot `index` of `count` as (right, up, forward), `spacing` apart
+    pub fn slot(self, index: usize, count: usize, spacing: f32) -> Vec3 {
+        let i = index as f32;
+        match self {
+            FormationShape::V => {
+                let rank = index.div_ceil(2) as f32;
+                let side = if index % 2 == 1 { 1.0 } else { -1.0 };
+                Vec3::new(side * rank * spacing, 0.0, -rank * spacing)
+            }
+            FormationShape::Line => Vec3::new((i - (count.max(1) - 1) as f32 * 0.5) * spacing, 0.0, 0.0),
+            FormationShape::Echelon(side) => Vec3::new(side.signum() * i * spacing, 0.0, -i * spacing),
+        }
+    }
+}
+
+/// When a formation splits up and each member makes its own attack run
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FormationBreak {
+    /// The player is within this distance of the leader
+    PlayerWithin(f32),
+    /// Seconds after forming up
+    Time(f32),
+    /// This many members have been lost
+    Losses(usize),
+}
+
+/* ------------------------------- Events ------------------------------- */
+
+/// Splits a formation up; sent when its break trigger fires, or by anything else
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BreakFormation(pub Entity);
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Invisible leader that follows the path; members hold slots around it.
+/// Despawned once it has no members left.
+#[derive(Component, Clone, Debug)]
+pub struct Formation {
+    pub shape: FormationShape,
+    pub spacing: f32,
+    /// In slot order. Members that die drop out and the rest move up.
+    pub members: Vec<Entity>,
+    pub break_on: Option<FormationBreak>,
+    /// Members it started with
+    pub size: usize,
+    pub age: f32,
+}
+
+impl Formation {
+    pub fn new(shape: FormationShape, spacing: f32, members: Vec<Entity>, break_on: Option<FormationBreak>) -> Self {
+        Self {
+            shape,
+            spacing,
+            size: members.len(),
+            members,
+            break_on,
+            age: 0.0,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FormationMember {
+    pub formation: Entity,
+    /// Has been placed on its slot; until then it jumps straight there
+    pub formed: bool,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct FormationConfig {
+    /// How quickly members close on their slot, per second
+    pub catch_up: f32,
+    /// Speed of an attack run, relative to the formation's
+    pub attack_speed: f32,
+    /// How far past the player an attack run carries on
+    pub attack_overshoot: f32,
+    /// Least height kept above the ground or water on an attack run
+    pub attack_clearance: f32,
+}
+
+impl Default for FormationConfig {
+    fn default() -> Self {
+        Self {
+            catch_up: 3.0,
+            attack_speed: 1.3,
+            attack_overshoot: 200.0,
+            attack_clearance: 8.0,
+        }
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct FormationPlugin;
+
+impl Plugin for FormationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FormationConfig>()
+            .add_event::<BreakFormation>()
+            .add_systems(Update, (
+                trigger_formation_breaks,
+                break_formations,
+                hold_formation_slots,
+            ).chain().after(PathSet).before(EnemySet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Spawns the leader of a formation following `follower`. Members are given
+/// `FormationMember` pointing back at it.
+pub fn spawn_formation(commands: &mut Commands, formation: Formation, follower: PathFollower) -> Entity {
+    let position = follower.path.position_at_distance(0.0);
+    let members = formation.members.clone();
+    let leader = commands
+        .spawn((
+            Name::new("Formation"),
+            Transform::from_translation(position),
+            formation,
+            follower,
+            StateScoped(InRun),
+        ))
+        .id();
+    for member in members {
+        commands.entity(member).insert(FormationMember {
+            formation: leader,
+            formed: false,
+        });
+    }
+    leader
+}
+
+/// Drops dead members and fires break triggers
+fn trigger_formation_breaks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut formations: Query<(Entity, &mut Formation, &Transform)>,
+    members: Query<(), With<FormationMember>>,
+    players: Query<&Transform, With<PlayerFlight>>,
+    mut breaks: EventWriter<BreakFormation>,
+) {
+    let player = players.single().ok();
+    for (entity, mut formation, transform) in formations.iter_mut() {
+        formation.age += time.delta_secs();
+        formation.members.retain(|&member| members.contains(member));
+        if formation.members.is_empty() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let fire = match formation.break_on {
+            Some(FormationBreak::PlayerWithin(distance)) => {
+                player.is_some_and(|player| player.translation.distance(transform.translation) <= distance)
+            }
+            Some(FormationBreak::Time(seconds)) => formation.age >= seconds,
+            Some(FormationBreak::Losses(losses)) => formation.size - formation.members.len() >= losses,
+            None => false,
+        };
+        if fire {
+            breaks.write(BreakFormation(entity));
+        }
+    }
+}
+
+/// Sends each member of a broken formation on its own run at the player,
+/// and does the same for members whose leader reached the end of its path
+fn break_formations(
+    mut commands: Commands,
+    mut breaks: EventReader<BreakFormation>,
+    config: Res<FormationConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    archetypes: Res<Assets<EnemyArchetype>>,
+    formations: Query<(&Formation, Option<&PathFollower>)>,
+    members: Query<(Entity, &FormationMember, &Transform, &EnemyKind)>,
+    players: Query<(&Transform, &Velocity), With<PlayerFlight>>,
+) {
+    let broken: Vec<Entity> = breaks.read().map(|event| event.0).collect();
+    let player = players.single().ok();
+    let course = &course.0;
+    let water_level = render_config.water_level_offset;
+
+    for (entity, member, transform, kind) in members.iter() {
+        let leader = formations.get(member.formation).ok();
+        if leader.is_some() && !broken.contains(&member.formation) {
+            continue;
+        }
+
+        let forward = (transform.rotation * Vec3::Z).normalize_or(Vec3::Z);
+        let start = transform.translation;
+        let (target, heading) = match player {
+            Some((player, velocity)) => (player.translation, velocity.0.normalize_or(forward)),
+            None => (start + forward * config.attack_overshoot, forward),
+        };
+        let points: Vec<Vec3> = [
+            start,
+            start + forward * 40.0,
+            target,
+            target + heading * config.attack_overshoot,
+        ]
+        .into_iter()
+        .map(|point| {
+            let floor = course.height(point.xz()).max(water_level) + config.attack_clearance;
+            point.with_y(point.y.max(floor))
+        })
+        .collect();
+
+        let speed = leader
+            .and_then(|(_, follower)| follower)
+            .map_or(0.0, PathFollower::current_speed);
+        let speed = if speed > 0.0 {
+            speed
+        } else {
+            archetypes.get(&kind.0).map_or(40.0, |archetype| archetype.speed)
+        };
+        let path = Arc::new(MeasuredPath::new(Arc::new(CatmullRomPath::new(points))));
+        let mut follower = PathFollower::new(path, speed * config.attack_speed, PathEnd::Despawn);
+        if archetypes.get(&kind.0).is_some_and(|archetype| !archetype.banks) {
+            follower = follower.without_banking();
+        }
+        commands.entity(entity).remove::<FormationMember>().insert(follower);
+    }
+
+    for formation in broken {
+        if let Ok(mut leader) = commands.get_entity(formation) {
+            leader.despawn();
+        }
+    }
+}
+
+/// Eases every member towards its slot, which is laid out in the leader's frame
+fn hold_formation_slots(
+    time: Res<Time>,
+    config: Res<FormationConfig>,
+    formations: Query<(&Formation, &Transform), Without<FormationMember>>,
+    mut members: Query<(&mut FormationMember, &mut Transform, &mut Velocity)>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    let blend = 1.0 - (-config.catch_up * dt).exp();
+
+    for (formation, leader) in formations.iter() {
+        let count = formation.members.len();
+        for (index, &member) in formation.members.iter().enumerate() {
+            let Ok((mut slot_holder, mut transform, mut velocity)) = members.get_mut(member) else {
+                continue;
+            };
+            // Nose is local +Z, so the right wing is local -X
+            let slot = formation.shape.slot(index, count, formation.spacing);
+            let target = leader.transform_point(Vec3::new(-slot.x, slot.y, slot.z));
+
+            if !slot_holder.formed {
+                slot_holder.formed = true;
+                transform.translation = target;
+                transform.rotation = leader.rotation;
+                continue;
+            }
+            let position = transform.translation.lerp(target, blend);
+            velocity.0 = (position - transform.translation) / dt;
+            transform.translation = position;
+            transform.rotation = transform.rotation.slerp(leader.rotation, blend);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::game::{Collider, CollisionLayer, CrashCause, CrashSet, Crashed, FlightConfig, FlightSet, GameState, Health, InRun, PlayerFlight, PlayerRespawned, Points, RiverCourse};
+use crate::heightmap_material::GpuHeightmapRenderConfig;
+
+/* ----------------------------- Components ----------------------------- */
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Fuel {
+    pub amount: f32,
+    pub capacity: f32,
+}
+
+impl Fuel {
+    pub fn full(capacity: f32) -> Self {
+        Self {
+            amount: capacity,
+            capacity,
+        }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.capacity > 0.0 {
+            (self.amount / self.capacity).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Refuels the player while they fly over it
+#[derive(Component)]
+pub struct FuelDepot {
+    /// Distance along the river axis the depot was placed at
+    pub along: f32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct FuelConfig {
+    pub capacity: f32,
+    /// Units per second burnt at minimum speed
+    pub idle_drain: f32,
+    /// Extra units per second burnt at full throttle
+    pub throttle_drain: f32,
+    /// Units per second gained while over a depot
+    pub refuel_rate: f32,
+    /// Fraction of the tank below which the warning shows
+    pub low_fuel_fraction: f32,
+    pub depot_points: u32,
+    pub depot_spacing: f32,
+    /// Random extra distance added to each spacing
+    pub depot_spacing_jitter: f32,
+    /// Horizontal distance within which a depot refuels the plane
+    pub depot_radius: f32,
+    /// How far ahead of the plane depots are placed
+    pub spawn_ahead: f32,
+    /// How far behind the plane depots are removed
+    pub despawn_behind: f32,
+}
+
+impl Default for FuelConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 100.0,
+            idle_drain: 2.0,
+            throttle_drain: 4.0,
+            refuel_rate: 30.0,
+            low_fuel_fraction: 0.25,
+            depot_points: 80,
+            depot_spacing: 350.0,
+            depot_spacing_jitter: 150.0,
+            depot_radius: 8.0,
+            spawn_ahead: 400.0,
+            despawn_behind: 80.0,
+        }
+    }
+}
+
+/// Where the next depot goes, in distance along the river axis
+#[derive(Resource, Default)]
+struct NextFuelDepot {
+    along: f32,
+}
+
+#[derive(Resource)]
+struct FuelDepotAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct FuelPlugin;
+
+impl Plugin for FuelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FuelConfig>()
+            .init_resource::<NextFuelDepot>()
+            .add_systems(Startup, setup_fuel_depot_assets)
+            .add_systems(OnEnter(InRun), reset_fuel_depots)
+            .add_systems(Update, (
+                attach_player_fuel,
+                drain_fuel,
+                refuel_at_depots,
+                rewind_fuel_depots,
+                stream_fuel_depots,
+                crash_when_fuel_empty,
+            ).chain().after(FlightSet).before(CrashSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn setup_fuel_depot_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(FuelDepotAssets {
+        mesh: meshes.add(Cuboid::new(6.0, 3.0, 10.0)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.85, 0.15, 0.1),
+            emissive: LinearRgba::rgb(0.4, 0.05, 0.0),
+            ..default()
+        }),
+    });
+}
+
+fn reset_fuel_depots(mut next: ResMut<NextFuelDepot>, config: Res<FuelConfig>) {
+    // Give the player a stretch of river before the first depot
+    next.along = config.depot_spacing * 0.5;
+}
+
+/// Clears the depots and starts placing them again from the respawn point,
+/// so the replayed stretch isn't left without fuel
+fn rewind_fuel_depots(
+    mut commands: Commands,
+    mut respawned: EventReader<PlayerRespawned>,
+    config: Res<FuelConfig>,
+    mut next: ResMut<NextFuelDepot>,
+    depots: Query<Entity, With<FuelDepot>>,
+) {
+    let Some(respawn) = respawned.read().last() else {
+        return;
+    };
+    for entity in depots.iter() {
+        commands.entity(entity).despawn();
+    }
+    next.along = respawn.along + config.depot_spacing * 0.5;
+}
+
+fn attach_player_fuel(
+    mut commands: Commands,
+    config: Res<FuelConfig>,
+    players: Query<Entity, (With<PlayerFlight>, Without<Fuel>)>,
+) {
+    for entity in players.iter() {
+        commands.entity(entity).insert(Fuel::full(config.capacity));
+    }
+}
+
+fn drain_fuel(
+    time: Res<Time>,
+    config: Res<FuelConfig>,
+    flight_config: Res<FlightConfig>,
+    mut players: Query<(&PlayerFlight, &mut Fuel)>,
+) {
+    let speed_range = (flight_config.max_speed - flight_config.min_speed).max(f32::EPSILON);
+    for (flight, mut fuel) in players.iter_mut() {
+        let throttle = ((flight.speed - flight_config.min_speed) / speed_range).clamp(0.0, 1.0);
+        let drain = config.idle_drain + config.throttle_drain * throttle;
+        fuel.amount = (fuel.amount - drain * time.delta_secs()).max(0.0);
+    }
+}
+
+fn refuel_at_depots(
+    time: Res<Time>,
+    config: Res<FuelConfig>,
+    depots: Query<&Transform, With<FuelDepot>>,
+    mut players: Query<(&Transform, &mut Fuel), Without<FuelDepot>>,
+) {
+    for (transform, mut fuel) in players.iter_mut() {
+        let over_depot = depots.iter().any(|depot| {
+            depot.translation.xz().distance(transform.translation.xz()) <= config.depot_radius
+        });
+        if over_depot {
+            fuel.amount = (fuel.amount + config.refuel_rate * time.delta_secs()).min(fuel.capacity);
+        }
+    }
+}
+
+/// Places depots on the water ahead of the player and clears the ones left behind
+fn stream_fuel_depots(
+    mut commands: Commands,
+    config: Res<FuelConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    assets: Res<FuelDepotAssets>,
+    mut next: ResMut<NextFuelDepot>,
+    players: Query<&PlayerFlight>,
+    depots: Query<(Entity, &FuelDepot)>,
+) {
+    let Ok(flight) = players.single() else {
+        return;
+    };
+    let course = &course.0;
+    let mut rng = rand::rng();
+
+    while next.along < flight.along + config.spawn_ahead {
+        let along = next.along;
+        // Keep depots inside the water channel so they float rather than sit on the bank
+        let half_width = course.river_width_at(along) * 0.3;
+        let lateral = course.meander(along) + rng.random_range(-half_width..=half_width);
+        let pos = course.axis_point(along, lateral);
+        let y = course.height(pos).max(render_config.water_level_offset) + 1.5;
+
+        commands.spawn((
+            Name::new("Fuel Depot"),
+            Mesh3d(assets.mesh.clone()),
+            MeshMaterial3d(assets.material.clone()),
+            Transform::from_xyz(pos.x, y, pos.y),
+            FuelDepot { along },
+            Collider::cuboid(Vec3::new(3.0, 1.5, 5.0), CollisionLayer::FuelDepot),
+            Health::new(1.0),
+            Points(config.depot_points),
+            StateScoped(InRun),
+        ));
+
+        next.along += config.depot_spacing + rng.random_range(0.0..=config.depot_spacing_jitter);
+    }
+
+    for (entity, depot) in depots.iter() {
+        if depot.along < flight.along - config.despawn_behind {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn crash_when_fuel_empty(mut commands: Commands, players: Query<(Entity, &Fuel), Without<Crashed>>) {
+    for (entity, fuel) in players.iter() {
+        if fuel.amount <= 0.0 {
+            commands.entity(entity).insert(Crashed {
+                cause: CrashCause::OutOfFuel,
+            });
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use bevy::prelude::*;
+
+use crate::game::{predict_bomb_impact, BombBay, BombConfig, Boss, BossDefeated, BossPhaseChanged, Fuel, FuelConfig, HighScoreTable, InRun, Lives, RiverCourse, Score, Velocity, Weapon, WeaponDef, WeaponLimit, WeaponLoadout};
+use crate::heightmap_material::GpuHeightmapRenderConfig;
+
+/* ----------------------------- Components ----------------------------- */
+
+#[derive(Component)]
+struct ScoreText;
+
+#[derive(Component)]
+struct DistanceText;
+
+#[derive(Component)]
+struct HighScoreText;
+
+#[derive(Component)]
+struct LivesText;
+
+#[derive(Component)]
+struct WeaponText;
+
+/// Ring on the ground where a bomb dropped now would land
+#[derive(Component)]
+struct BombSight;
+
+#[derive(Component)]
+struct FuelGaugeFill;
+
+#[derive(Component)]
+struct LowFuelWarning;
+
+/// Holds the name and health bar of the boss that last changed phase;
+/// hidden while there is no boss
+#[derive(Component, Default)]
+struct BossBar {
+    boss: Option<Entity>,
+}
+
+#[derive(Component)]
+struct BossNameText;
+
+#[derive(Component)]
+struct BossHealthFill;
+
+/// Big centre text announcing boss phases, shown for `BOSS_BANNER_TIME`
+#[derive(Component, Default)]
+struct BossBanner {
+    remaining: f32,
+}
+
+const BOSS_BANNER_TIME: f32 = 3.0;
+
+/* ------------------------------- Plugin ------------------------------- */
+
+/// In-game overlay: score, distance, weapon, lives, best score, the fuel gauge,
+/// the bomb sight and the boss health bar
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(InRun), (spawn_hud, spawn_fuel_gauge, spawn_bomb_sight, spawn_boss_bar))
+            .add_systems(Update, (update_hud_text, update_weapon_text, update_fuel_gauge, update_bomb_sight).run_if(in_state(InRun)))
+            .add_systems(Update, (update_boss_bar, show_boss_banner).run_if(in_state(InRun)));
+    }
+}
+
+fn hud_text(text: &str, size: f32) -> (Text, TextFont, TextColor) {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: size,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+    )
+}
+
+fn spawn_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(0.0),
+                padding: UiRect::axes(Val::Px(24.0), Val::Px(16.0)),
+                justify_content: JustifyContent::SpaceBetween,
+                ..default()
+            },
+            StateScoped(InRun),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|column| {
+                    column.spawn((hud_text("SCORE 0", 32.0), ScoreText));
+                    column.spawn((hud_text("0 m", 20.0), DistanceText));
+                    column.spawn((hud_text("", 20.0), WeaponText));
+                });
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                })
+                .with_children(|column| {
+                    column.spawn((hud_text("HI 0", 24.0), HighScoreText));
+                    column.spawn((hud_text("LIVES 0", 24.0), LivesText));
+                });
+        });
+}
+
+fn update_hud_text(
+    score: Res<Score>,
+    table: Res<HighScoreTable>,
+    lives: Res<Lives>,
+    mut texts: ParamSet<(
+        Query<&mut Text, With<ScoreText>>,
+        Query<&mut Text, With<DistanceText>>,
+        Query<&mut Text, With<HighScoreText>>,
+        Query<&mut Text, With<LivesText>>,
+    )>,
+) {
+    for mut text in texts.p0().iter_mut() {
+        text.0 = format!("SCORE {}", score.points);
+    }
+    for mut text in texts.p1().iter_mut() {
+        text.0 = format!("{:.0} m", score.distance);
+    }
+    for mut text in texts.p2().iter_mut() {
+        text.0 = format!("HI {}", table.best().max(score.points));
+    }
+    for mut text in texts.p3().iter_mut() {
+        text.0 = format!("LIVES {}", lives.remaining);
+    }
+}
+
+fn update_weapon_text(
+    defs: Res<Assets<WeaponDef>>,
+    players: Query<&Weapon, With<WeaponLoadout>>,
+    mut texts: Query<&mut Text, With<WeaponText>>,
+) {
+    let label = players
+        .single()
+        .ok()
+        .and_then(|weapon| defs.get(&weapon.def).map(|def| (weapon, def)))
+        .map_or(String::new(), |(weapon, def)| {
+            let name = def.name.to_uppercase();
+            match def.limit {
+                WeaponLimit::Unlimited => name,
+                WeaponLimit::Ammo(_) => format!("{name}  x{}", weapon.ammo.unwrap_or(0)),
+                WeaponLimit::Heat { .. } if weapon.overheated => format!("{name}  OVERHEAT"),
+                WeaponLimit::Heat { .. } => format!("{name}  HEAT {:.0}%", weapon.heat.min(1.0) * 100.0),
+            }
+        });
+
+    for mut text in texts.iter_mut() {
+        if text.0 != label {
+            text.0.clone_from(&label);
+        }
+    }
+}
+
+fn spawn_fuel_gauge(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(24.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-120.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            StateScoped(InRun),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("LOW FUEL"),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.2, 0.1)),
+                Visibility::Hidden,
+                LowFuelWarning,
+            ));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(240.0),
+                        height: Val::Px(18.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                ))
+                .with_children(|gauge| {
+                    gauge.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(1.0, 0.8, 0.1)),
+                        FuelGaugeFill,
+                    ));
+                });
+        });
+}
+
+fn update_fuel_gauge(
+    time: Res<Time>,
+    config: Res<FuelConfig>,
+    players: Query<&Fuel>,
+    mut fill: Query<(&mut Node, &mut BackgroundColor), With<FuelGaugeFill>>,
+    mut warning: Query<&mut Visibility, With<LowFuelWarning>>,
+) {
+    let Ok(fuel) = players.single() else {
+        return;
+    };
+    let fraction = fuel.fraction();
+    let low = fraction < config.low_fuel_fraction;
+
+    for (mut node, mut color) in fill.iter_mut() {
+        node.width = Val::Percent(fraction * 100.0);
+        color.0 = if low {
+            Color::srgb(1.0, 0.2, 0.1)
+        } else {
+            Color::srgb(1.0, 0.8, 0.1)
+        };
+    }
+
+    // Blink at 2 Hz while low
+    let blink_on = (time.elapsed_secs() * 4.0) as u32 % 2 == 0;
+    for mut visibility in warning.iter_mut() {
+        *visibility = if low && blink_on {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn spawn_bomb_sight(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Name::new("Bomb sight"),
+        Mesh3d(meshes.add(Torus::new(2.5, 3.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(1.0, 0.2, 0.1, 0.8),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })),
+        Transform::default(),
+        Visibility::Hidden,
+        BombSight,
+        StateScoped(InRun),
+    ));
+}
+
+fn update_bomb_sight(
+    config: Res<BombConfig>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    players: Query<(&Transform, &Velocity), With<BombBay>>,
+    mut sights: Query<(&mut Transform, &mut Visibility), (With<BombSight>, Without<BombBay>)>,
+) {
+    let impact = players.single().ok().and_then(|(transform, velocity)| {
+        predict_bomb_impact(
+            &config,
+            &course.0,
+            render_config.water_level_offset,
+            transform.translation - Vec3::Y * 1.5,
+            velocity.0,
+        )
+    });
+
+    for (mut transform, mut visibility) in sights.iter_mut() {
+        match impact {
+            Some(impact) => {
+                // Float just above the surface so the ring isn't buried in the water
+                transform.translation = impact + Vec3::Y * 0.5;
+                *visibility = Visibility::Inherited;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+fn spawn_boss_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(20.0),
+                left: Val::Percent(50.0),
+                margin: UiRect::left(Val::Px(-200.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            BossBar::default(),
+            StateScoped(InRun),
+        ))
+        .with_children(|parent| {
+            parent.spawn((hud_text("", 22.0), BossNameText));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(400.0),
+                        height: Val::Px(14.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.9, 0.15, 0.1)),
+                        BossHealthFill,
+                    ));
+                });
+        });
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Percent(30.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            StateScoped(InRun),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.35, 0.1)),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Visibility::Hidden,
+                BossBanner::default(),
+            ));
+        });
+}
+
+fn update_boss_bar(
+    mut changed: EventReader<BossPhaseChanged>,
+    bosses: Query<&Boss>,
+    mut bars: Query<(&mut Visibility, &mut BossBar)>,
+    mut names: Query<&mut Text, With<BossNameText>>,
+    mut fill: Query<&mut Node, With<BossHealthFill>>,
+) {
+    let latest = changed.read().last().map(|event| event.boss);
+    let mut shown = None;
+    for (mut visibility, mut bar) in bars.iter_mut() {
+        if latest.is_some() {
+            bar.boss = latest;
+        }
+        shown = bar.boss.and_then(|entity| bosses.get(entity).ok());
+        *visibility = if shown.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    let Some(boss) = shown else {
+        return;
+    };
+
+    let label = boss.name.to_uppercase();
+    for mut text in names.iter_mut() {
+        if text.0 != label {
+            text.0.clone_from(&label);
+        }
+    }
+    for mut node in fill.iter_mut() {
+        node.width = Val::Percent(boss.fraction() * 100.0);
+    }
+}
+
+fn show_boss_banner(
+    time: Res<Time>,
+    mut changed: EventReader<BossPhaseChanged>,
+    mut defeated: EventReader<BossDefeated>,
+    mut banners: Query<(&mut Text, &mut Visibility, &mut BossBanner)>,
+) {
+    let mut message = None;
+    for event in changed.read() {
+        message = Some(if event.phase == 0 {
+            format!("{}\n{}", event.name.to_uppercase(), event.phase_name.to_uppercase())
+        } else {
+            format!("PHASE {}/{}\n{}", event.phase + 1, event.phases, event.phase_name.to_uppercase())
+        });
+    }
+    for event in defeated.read() {
+        message = Some(format!("{} DESTROYED", event.name.to_uppercase()));
+    }
+
+    for (mut text, mut visibility, mut banner) in banners.iter_mut() {
+        if let Some(message) = &message {
+            text.0.clone_from(message);
+            banner.remaining = BOSS_BANNER_TIME;
+        }
+        banner.remaining = (banner.remaining - time.delta_secs()).max(0.0);
+        *visibility = if banner.remaining > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,37 @@
+use bevy::asset::LoadedFolder;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::game::{BossDef, BossLibrary, EnemyArchetype, EnemyLibrary, WeaponDef, WeaponLibrary};
+
+/// The enemy, weapon and boss libraries with the assets they index, for systems that
+/// look definitions up by name
+#[derive(SystemParam)]
+pub struct Libraries<'w> {
+    pub enemies: Res<'w, EnemyLibrary>,
+    pub weapons: Res<'w, WeaponLibrary>,
+    pub bosses: Res<'w, BossLibrary>,
+    pub folders: Res<'w, Assets<LoadedFolder>>,
+    pub archetypes: Res<'w, Assets<EnemyArchetype>>,
+    pub weapon_defs: Res<'w, Assets<WeaponDef>>,
+    pub boss_defs: Res<'w, Assets<BossDef>>,
+}
+
+impl Libraries<'_> {
+    /// Whether any enemy archetype has loaded yet
+    pub fn has_enemies(&self) -> bool {
+        !self.enemies.archetypes(&self.folders, &self.archetypes).is_empty()
+    }
+
+    pub fn enemy(&self, name: &str) -> Option<(Handle<EnemyArchetype>, &EnemyArchetype)> {
+        self.enemies.find(name, &self.folders, &self.archetypes)
+    }
+
+    pub fn weapon(&self, name: &str) -> Option<(Handle<WeaponDef>, &WeaponDef)> {
+        self.weapons.find(name, &self.folders, &self.weapon_defs)
+    }
+
+    pub fn boss(&self, name: &str) -> Option<(Handle<BossDef>, &BossDef)> {
+        self.bosses.find(name, &self.folders, &self.boss_defs)
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use crate::game::{Boss, Collider, CollisionLayer, CrashSet, Destroyed, GameState, InRun, PlayerCrashed, RiverCourse};
+use crate::rendering::plane::{spawn_player_plane, Plane};
+
+/* ------------------------------- Events ------------------------------- */
+
+/// The plane is back at the checkpoint, `along` the river axis. Anything streamed
+/// ahead of the player should rewind to here.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlayerRespawned {
+    pub along: f32,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Ignores damage and crashes until it runs out; the plane blinks meanwhile
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Invulnerable {
+    pub remaining: f32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Lives {
+    pub remaining: u32,
+}
+
+#[derive(Resource, Clone)]
+pub struct LivesConfig {
+    pub starting_lives: u32,
+    /// Seconds between a crash and the respawn
+    pub respawn_delay: f32,
+    pub invulnerability: f32,
+    pub blink_interval: f32,
+    /// Enemies this close to the respawn point are removed
+    pub clear_radius: f32,
+}
+
+impl Default for LivesConfig {
+    fn default() -> Self {
+        Self {
+            starting_lives: 3,
+            respawn_delay: 1.5,
+            invulnerability: 3.0,
+            blink_interval: 0.1,
+            clear_radius: 150.0,
+        }
+    }
+}
+
+/// Where the player comes back after a crash, in distance along the river axis.
+/// Moves to each destroyed bridge.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Checkpoint {
+    pub along: f32,
+}
+
+#[derive(Resource)]
+struct PendingRespawn {
+    remaining: f32,
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct LivesPlugin;
+
+impl Plugin for LivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LivesConfig>()
+            .init_resource::<Lives>()
+            .init_resource::<Checkpoint>()
+            .add_event::<PlayerRespawned>()
+            .add_systems(OnEnter(InRun), reset_lives)
+            .add_systems(Update, (
+                record_bridge_checkpoint,
+                lose_life_on_crash,
+                respawn_player,
+                tick_invulnerability,
+            ).chain().after(CrashSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn reset_lives(mut commands: Commands, config: Res<LivesConfig>) {
+    commands.insert_resource(Lives {
+        remaining: config.starting_lives,
+    });
+    commands.insert_resource(Checkpoint::default());
+    commands.remove_resource::<PendingRespawn>();
+}
+
+fn record_bridge_checkpoint(
+    mut destroyed: EventReader<Destroyed>,
+    course: Res<RiverCourse>,
+    mut checkpoint: ResMut<Checkpoint>,
+) {
+    for event in destroyed.read() {
+        if event.layer == CollisionLayer::Bridge {
+            let along = course.0.distance_along(event.position.xz());
+            checkpoint.along = checkpoint.along.max(along);
+        }
+    }
+}
+
+fn lose_life_on_crash(
+    mut commands: Commands,
+    mut crashes: EventReader<PlayerCrashed>,
+    config: Res<LivesConfig>,
+    mut lives: ResMut<Lives>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(crash) = crashes.read().last() else {
+        return;
+    };
+    lives.remaining = lives.remaining.saturating_sub(1);
+    info!("💥 Player crashed ({:?}) at {:.0}, {} lives left", crash.cause, crash.position, lives.remaining);
+
+    if lives.remaining == 0 {
+        next_state.set(GameState::GameOver);
+    } else {
+        commands.insert_resource(PendingRespawn {
+            remaining: config.respawn_delay,
+        });
+    }
+}
+
+fn respawn_player(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<LivesConfig>,
+    asset_server: Res<AssetServer>,
+    course: Res<RiverCourse>,
+    checkpoint: Res<Checkpoint>,
+    pending: Option<ResMut<PendingRespawn>>,
+    mut respawned: EventWriter<PlayerRespawned>,
+    // Bosses hold station off the player and take their weak points with them
+    enemies: Query<(Entity, &Transform, &Collider), (Without<Boss>, Without<ChildOf>)>,
+) {
+    let Some(mut pending) = pending else {
+        return;
+    };
+    pending.remaining -= time.delta_secs();
+    if pending.remaining > 0.0 {
+        return;
+    }
+    commands.remove_resource::<PendingRespawn>();
+
+    // Don't respawn into something that kills the player straight away
+    let spawn_point = course.0.river_center(checkpoint.along);
+    for (entity, transform, collider) in enemies.iter() {
+        if collider.layer == CollisionLayer::Enemy
+            && transform.translation.xz().distance(spawn_point) <= config.clear_radius
+        {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    let plane = spawn_player_plane(&mut commands, &asset_server, checkpoint.along);
+    commands.entity(plane).insert(Invulnerable {
+        remaining: config.invulnerability,
+    });
+    respawned.write(PlayerRespawned {
+        along: checkpoint.along,
+    });
+}
+
+fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<LivesConfig>,
+    mut players: Query<(Entity, &mut Invulnerable, &mut Visibility), With<Plane>>,
+) {
+    for (entity, mut invulnerable, mut visibility) in players.iter_mut() {
+        invulnerable.remaining -= time.delta_secs();
+        if invulnerable.remaining <= 0.0 {
+            commands.entity(entity).remove::<Invulnerable>();
+            *visibility = Visibility::Inherited;
+            continue;
+        }
+
+        let phase = (invulnerable.remaining / config.blink_interval.max(0.01)) as u32;
+        *visibility = if phase % 2 == 0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,39 @@
+pub mod bombs;
+pub mod bosses;
+pub mod bridges;
+pub mod collision;
+pub mod crash;
+pub mod enemies;
+pub mod flight;
+pub mod formations;
+pub mod fuel;
+pub mod hud;
+pub mod libraries;
+pub mod lives;
+pub mod score;
+pub mod state;
+pub mod steering;
+pub mod targeting;
+pub mod turrets;
+pub mod waves;
+pub mod weapons;
+
+pub use bombs::*;
+pub use bosses::*;
+pub use bridges::*;
+pub use collision::*;
+pub use crash::*;
+pub use enemies::*;
+pub use flight::*;
+pub use formations::*;
+pub use fuel::*;
+pub use hud::*;
+pub use libraries::*;
+pub use lives::*;
+pub use score::*;
+pub use state::*;
+pub use steering::*;
+pub use targeting::*;
+pub use turrets::*;
+pub use waves::*;
+pub use weapons::*;
@@ -0,0 +1,293 @@
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{CollisionLayer, CollisionSet, Destroyed, GameState, InRun, PlayerFlight};
+use crate::heightmap_material::GpuHeightmapConfigUI;
+
+const HIGH_SCORE_FILE: &str = "highscores.ron";
+const HIGH_SCORE_ENTRIES: usize = 10;
+const INITIALS_LENGTH: usize = 3;
+
+/* ----------------------------- Resources ------------------------------ */
+
+/// Score for the current run
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct Score {
+    pub points: u32,
+    /// Furthest distance flown along the river
+    pub distance: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HighScoreEntry {
+    pub initials: String,
+    pub score: u32,
+    pub distance: f32,
+    /// Terrain seed the run was flown on
+    pub seed: f32,
+    /// `YYYY-MM-DD`, UTC
+    pub date: String,
+}
+
+/// Best runs, highest first. Saved to `highscores.ron` in the working directory.
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+pub struct HighScoreTable {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScoreTable {
+    pub fn best(&self) -> u32 {
+        self.entries.first().map_or(0, |entry| entry.score)
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < HIGH_SCORE_ENTRIES
+                || self.entries.last().is_some_and(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        self.entries.push(entry);
+        self.entries.sort_by(|a, b| b.score.cmp(&a.score));
+        self.entries.truncate(HIGH_SCORE_ENTRIES);
+    }
+
+    fn load() -> Self {
+        let Ok(text) = fs::read_to_string(HIGH_SCORE_FILE) else {
+            return Self::default();
+        };
+        ron::from_str(&text).unwrap_or_else(|err| {
+            warn!("Ignoring unreadable {HIGH_SCORE_FILE}: {err}");
+            Self::default()
+        })
+    }
+
+    fn save(&self) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())
+            .and_then(|text| fs::write(HIGH_SCORE_FILE, text).map_err(|err| err.to_string()));
+        if let Err(err) = result {
+            warn!("Could not save {HIGH_SCORE_FILE}: {err}");
+        }
+    }
+}
+
+/// Initials being typed on the game over screen for a qualifying score
+#[derive(Resource, Default)]
+pub struct PendingHighScore {
+    pub initials: String,
+}
+
+#[derive(Component)]
+struct HighScoreListText;
+
+#[derive(Component)]
+struct InitialsPromptText;
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .insert_resource(HighScoreTable::load())
+            .add_systems(OnEnter(InRun), reset_score)
+            .add_systems(Update, (award_points, track_distance).after(CollisionSet).run_if(in_state(GameState::Playing)))
+            .add_systems(OnEnter(GameState::GameOver), (prepare_high_score_entry, spawn_high_score_panel).chain())
+            .add_systems(Update, (type_initials, update_high_score_panel).chain().run_if(in_state(GameState::GameOver)))
+            .add_systems(OnExit(GameState::GameOver), clear_pending_high_score);
+    }
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+fn award_points(mut destroyed: EventReader<Destroyed>, mut score: ResMut<Score>) {
+    for event in destroyed.read() {
+        if event.layer != CollisionLayer::Player {
+            score.points += event.points;
+        }
+    }
+}
+
+fn track_distance(players: Query<&PlayerFlight>, mut score: ResMut<Score>) {
+    for flight in players.iter() {
+        if flight.along > score.distance {
+            score.distance = flight.along;
+        }
+    }
+}
+
+fn prepare_high_score_entry(mut commands: Commands, score: Res<Score>, table: Res<HighScoreTable>) {
+    if table.qualifies(score.points) {
+        commands.insert_resource(PendingHighScore::default());
+    }
+}
+
+fn clear_pending_high_score(mut commands: Commands) {
+    commands.remove_resource::<PendingHighScore>();
+}
+
+fn type_initials(
+    mut commands: Commands,
+    mut keys: EventReader<KeyboardInput>,
+    pending: Option<ResMut<PendingHighScore>>,
+    score: Res<Score>,
+    terrain: Res<GpuHeightmapConfigUI>,
+    mut table: ResMut<HighScoreTable>,
+) {
+    let Some(mut pending) = pending else {
+        keys.clear();
+        return;
+    };
+    // Drop keys still buffered from flying
+    if pending.is_added() {
+        keys.clear();
+        return;
+    }
+
+    for key in keys.read() {
+        if !key.state.is_pressed() {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Character(text) => {
+                for c in text.chars().filter(char::is_ascii_alphanumeric) {
+                    if pending.initials.len() < INITIALS_LENGTH {
+                        pending.initials.push(c.to_ascii_uppercase());
+                    }
+                }
+            }
+            Key::Backspace => {
+                pending.initials.pop();
+            }
+            Key::Enter if !pending.initials.is_empty() => {
+                table.insert(HighScoreEntry {
+                    initials: pending.initials.clone(),
+                    score: score.points,
+                    distance: score.distance,
+                    seed: terrain.seed,
+                    date: today(),
+                });
+                table.save();
+                commands.remove_resource::<PendingHighScore>();
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn spawn_high_score_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(48.0),
+                top: Val::Px(48.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            StateScoped(GameState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("HIGH SCORES"),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.8, 0.1)),
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                HighScoreListText,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.4, 1.0, 0.4)),
+                InitialsPromptText,
+            ));
+        });
+}
+
+fn update_high_score_panel(
+    score: Res<Score>,
+    table: Res<HighScoreTable>,
+    pending: Option<Res<PendingHighScore>>,
+    mut list: Query<&mut Text, (With<HighScoreListText>, Without<InitialsPromptText>)>,
+    mut prompt: Query<&mut Text, (With<InitialsPromptText>, Without<HighScoreListText>)>,
+) {
+    for mut text in list.iter_mut() {
+        text.0 = if table.entries.is_empty() {
+            "No scores yet".to_string()
+        } else {
+            table
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    format!(
+                        "{:>2}. {:<3} {:>7}  {:>6.0}m  seed {}  {}",
+                        i + 1,
+                        entry.initials,
+                        entry.score,
+                        entry.distance,
+                        entry.seed,
+                        entry.date
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+    }
+
+    for mut text in prompt.iter_mut() {
+        text.0 = match &pending {
+            Some(pending) => format!(
+                "New high score {}! Initials: {:_<3}  (Enter to save)",
+                score.points, pending.initials
+            ),
+            None => format!("Score {}", score.points),
+        };
+    }
+}
+
+/// UTC date as `YYYY-MM-DD`, from days since the Unix epoch
+fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let days = (secs / 86_400) as i64;
+
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
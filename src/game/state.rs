@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use bevy_egui::EguiPrimaryContextPass;
+
+use crate::game::PendingHighScore;
+
+/* ------------------------------- States ------------------------------- */
+
+/// Top level flow of the game. Entities tagged `StateScoped(GameState::X)`
+/// are despawned when `X` is exited.
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[states(scoped_entities)]
+pub enum GameState {
+    #[default]
+    Boot,
+    MainMenu,
+    Playing,
+    Paused,
+    GameOver,
+    /// Tool mode: egui windows and the free cameras
+    Editor,
+}
+
+/// Active while a run is in progress, paused or not.
+/// Gameplay entities are scoped to this so pausing doesn't despawn them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InRun;
+
+impl ComputedStates for InRun {
+    type SourceStates = GameState;
+
+    fn compute(state: GameState) -> Option<Self> {
+        matches!(state, GameState::Playing | GameState::Paused).then_some(InRun)
+    }
+}
+
+/// egui tool windows; only run in `GameState::Editor`
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EditorUiSet;
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .add_computed_state::<InRun>()
+            .enable_state_scoped_entities::<InRun>()
+            .configure_sets(EguiPrimaryContextPass, EditorUiSet.run_if(in_state(GameState::Editor)))
+            .add_systems(Update, finish_boot.run_if(in_state(GameState::Boot)))
+            .add_systems(Update, game_state_input)
+            .add_systems(OnEnter(GameState::MainMenu), spawn_main_menu)
+            .add_systems(OnEnter(GameState::Paused), spawn_pause_overlay)
+            .add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen);
+    }
+}
+
+fn finish_boot(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::MainMenu);
+}
+
+fn game_state_input(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    pending_high_score: Option<Res<PendingHighScore>>,
+) {
+    // Enter saves the initials first
+    let entering_initials = pending_high_score.is_some();
+    let next = match state.get() {
+        GameState::MainMenu if keyboard.just_pressed(KeyCode::Enter) => Some(GameState::Playing),
+        GameState::MainMenu if keyboard.just_pressed(KeyCode::F1) => Some(GameState::Editor),
+        GameState::Editor if keyboard.just_pressed(KeyCode::F1) => Some(GameState::MainMenu),
+        GameState::Playing if keyboard.just_pressed(KeyCode::Escape) => Some(GameState::Paused),
+        GameState::Paused if keyboard.just_pressed(KeyCode::Escape) => Some(GameState::Playing),
+        GameState::Paused if keyboard.just_pressed(KeyCode::KeyQ) => Some(GameState::MainMenu),
+        GameState::GameOver if !entering_initials && keyboard.just_pressed(KeyCode::Enter) => Some(GameState::MainMenu),
+        _ => None,
+    };
+    if let Some(next) = next {
+        next_state.set(next);
+    }
+}
+
+fn spawn_state_screen(commands: &mut Commands, state: GameState, title: &str, hint: &str, dim: f32) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, dim)),
+            StateScoped(state),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 64.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            parent.spawn((
+                Text::new(hint),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            ));
+        });
+}
+
+fn spawn_main_menu(mut commands: Commands) {
+    spawn_state_screen(
+        &mut commands,
+        GameState::MainMenu,
+        "WASTELAND INVADERS",
+        "Enter - start    F1 - editor",
+        0.6,
+    );
+}
+
+fn spawn_pause_overlay(mut commands: Commands) {
+    spawn_state_screen(
+        &mut commands,
+        GameState::Paused,
+        "PAUSED",
+        "Esc - resume    Q - quit to menu",
+        0.4,
+    );
+}
+
+fn spawn_game_over_screen(mut commands: Commands) {
+    spawn_state_screen(
+        &mut commands,
+        GameState::GameOver,
+        "GAME OVER",
+        "Enter - back to menu",
+        0.6,
+    );
+}
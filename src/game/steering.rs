@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::game::{EnemyMotion, EnemySet, FormationMember, GameState, PlayerFlight, RiverCourse, Velocity};
+use crate::heightmap_material::{GpuHeightmapRenderConfig, TerrainSampler};
+use crate::paths::{PathFollower, PathSet};
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// One steering force. Each asks for a change of velocity, scaled by its `weight`
+/// before they are added up. Seek, flee, pursue and evade all act on the player.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SteeringBehaviour {
+    /// Head straight for the player
+    Seek { weight: f32 },
+    /// Turn away from the player once within `radius`
+    Flee { weight: f32, radius: f32 },
+    /// Head for where the player will be, looking at most `lookahead` seconds ahead
+    Pursue { weight: f32, lookahead: f32 },
+    /// Turn away from where the player will be, once within `radius`
+    Evade { weight: f32, radius: f32, lookahead: f32 },
+    /// Drift about: steer at a point on a circle of `radius`, `distance` ahead, that moves
+    /// round by up to `jitter` radians a second
+    Wander { weight: f32, radius: f32, distance: f32, jitter: f32 },
+    /// Keep away from other steered enemies within `radius`
+    Separation { weight: f32, radius: f32 },
+    /// Climb and turn away from ground that would come within `clearance` in the next
+    /// `lookahead` seconds
+    AvoidTerrain { weight: f32, lookahead: f32, clearance: f32 },
+}
+
+/// Free movement for an archetype, used instead of its `movement` mode
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct SteeringDef {
+    pub behaviours: Vec<SteeringBehaviour>,
+    pub max_speed: f32,
+    /// Largest change of velocity per second
+    pub max_acceleration: f32,
+    /// Degrees per second
+    pub turn_rate: f32,
+    /// Height above the ground or water it appears at
+    pub altitude: f32,
+    /// Never drops closer than this to the ground or water
+    #[serde(default)]
+    pub min_height: f32,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+#[derive(Component, Clone, Debug)]
+pub struct Steering {
+    pub def: SteeringDef,
+    /// Where on the wander circle it is heading, in radians
+    pub wander_angle: f32,
+    /// Has been placed at its spawn point
+    pub placed: bool,
+}
+
+impl Steering {
+    pub fn new(def: SteeringDef) -> Self {
+        Self {
+            def,
+            wander_angle: 0.0,
+            placed: false,
+        }
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct SteeringPlugin;
+
+impl Plugin for SteeringPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, steer_enemies.after(PathSet).before(EnemySet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Velocity change that turns `velocity` into full speed along `direction`
+fn steer_towards(direction: Vec3, velocity: Vec3, max_speed: f32) -> Vec3 {
+    direction.normalize_or_zero() * max_speed - velocity
+}
+
+/// Ground or water height at `xz`
+fn surface_height(terrain: &TerrainSampler, water_level: f32, xz: Vec2) -> f32 {
+    terrain.height(xz).max(water_level)
+}
+
+fn steer_enemies(
+    time: Res<Time>,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    players: Query<(&Transform, &Velocity), With<PlayerFlight>>,
+    mut enemies: Query<
+        (Entity, &mut Steering, &EnemyMotion, &mut Transform, &mut Velocity),
+        (Without<PathFollower>, Without<FormationMember>, Without<PlayerFlight>),
+    >,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+    let terrain = &course.0;
+    let water_level = render_config.water_level_offset;
+    let player = players.single().ok().map(|(transform, velocity)| (transform.translation, velocity.0));
+    let neighbours: Vec<(Entity, Vec3)> = enemies
+        .iter()
+        .map(|(entity, _, _, transform, _)| (entity, transform.translation))
+        .collect();
+    let mut rng = rand::rng();
+
+    for (entity, mut steering, motion, mut transform, mut velocity) in enemies.iter_mut() {
+        let Steering { def, wander_angle, placed } = &mut *steering;
+
+        if !*placed {
+            *placed = true;
+            let xz = terrain.axis_point(motion.along, terrain.meander(motion.along) + motion.offset);
+            transform.translation = Vec3::new(xz.x, surface_height(terrain, water_level, xz) + def.altitude, xz.y);
+            // Set off back down the river towards the player
+            let axis = terrain.river_axis();
+            velocity.0 = Vec3::new(-axis.x, 0.0, -axis.y) * def.max_speed * 0.5;
+            continue;
+        }
+
+        let position = transform.translation;
+        let current = velocity.0;
+        let mut force = Vec3::ZERO;
+        for behaviour in &def.behaviours {
+            force += match *behaviour {
+                SteeringBehaviour::Seek { weight } => player.map_or(Vec3::ZERO, |(target, _)| {
+                    steer_towards(target - position, current, def.max_speed) * weight
+                }),
+                SteeringBehaviour::Flee { weight, radius } => player.map_or(Vec3::ZERO, |(target, _)| {
+                    if position.distance(target) < radius {
+                        steer_towards(position - target, current, def.max_speed) * weight
+                    } else {
+                        Vec3::ZERO
+                    }
+                }),
+                SteeringBehaviour::Pursue { weight, lookahead } => {
+                    player.map_or(Vec3::ZERO, |(target, target_velocity)| {
+                        let ahead = (position.distance(target) / def.max_speed.max(1.0)).min(lookahead);
+                        steer_towards(target + target_velocity * ahead - position, current, def.max_speed) * weight
+                    })
+                }
+                SteeringBehaviour::Evade { weight, radius, lookahead } => {
+                    player.map_or(Vec3::ZERO, |(target, target_velocity)| {
+                        if position.distance(target) >= radius {
+                            return Vec3::ZERO;
+                        }
+                        let ahead = (position.distance(target) / def.max_speed.max(1.0)).min(lookahead);
+                        steer_towards(position - (target + target_velocity * ahead), current, def.max_speed) * weight
+                    })
+                }
+                SteeringBehaviour::Wander { weight, radius, distance, jitter } => {
+                    *wander_angle += rng.random_range(-jitter.abs()..=jitter.abs()) * dt;
+                    let heading = current.with_y(0.0).normalize_or(Vec3::Z);
+                    let side = Vec3::new(heading.z, 0.0, -heading.x);
+                    // Measured from the heading, so the angle keeps its meaning as the enemy turns
+                    let (sin, cos) = wander_angle.sin_cos();
+                    let offset = (heading * cos + side * sin) * radius;
+                    steer_towards(heading * distance + offset, current, def.max_speed) * weight
+                }
+                SteeringBehaviour::Separation { weight, radius } => {
+                    let away: Vec3 = neighbours
+                        .iter()
+                        .filter(|&&(other, _)| other != entity)
+                        .map(|&(_, other)| position - other)
+                        .filter(|offset| offset.length() < radius)
+                        // Closer neighbours push harder
+                        .map(|offset| offset.normalize_or_zero() / offset.length().max(0.1))
+                        .sum();
+                    if away == Vec3::ZERO {
+                        Vec3::ZERO
+                    } else {
+                        steer_towards(away, current, def.max_speed) * weight
+                    }
+                }
+                SteeringBehaviour::AvoidTerrain { weight, lookahead, clearance } => {
+                    avoid_terrain(terrain, water_level, position, current, lookahead, clearance) * def.max_speed * weight
+                }
+            };
+        }
+
+        let force = force.clamp_length_max(def.max_acceleration);
+        let mut desired = (current + force * dt).clamp_length_max(def.max_speed);
+
+        // Limit how fast the heading can swing round
+        let max_turn = def.turn_rate.to_radians() * dt;
+        if let (Some(from), Some(to)) = (current.try_normalize(), desired.try_normalize()) {
+            let angle = from.angle_between(to);
+            if angle > max_turn {
+                let axis = from.cross(to).try_normalize().unwrap_or(Vec3::Y);
+                desired = Quat::from_axis_angle(axis, max_turn) * from * desired.length();
+            }
+        }
+
+        let mut next = position + desired * dt;
+        let floor = surface_height(terrain, water_level, next.xz()) + def.min_height;
+        if next.y < floor {
+            next.y = floor;
+            desired.y = desired.y.max(0.0);
+        }
+        velocity.0 = desired;
+        transform.translation = next;
+        if let Some(direction) = desired.try_normalize() {
+            // Models face down their local +Z
+            transform.look_to(-direction, Vec3::Y);
+        }
+    }
+}
+
+/// Unit push away from terrain the enemy is about to fly into: up, plus downhill
+/// so it turns out of a valley wall rather than only climbing it
+fn avoid_terrain(terrain: &TerrainSampler, water_level: f32, position: Vec3, velocity: Vec3, lookahead: f32, clearance: f32) -> Vec3 {
+    const PROBES: [f32; 3] = [0.25, 0.5, 1.0];
+    const SLOPE_STEP: f32 = 2.0;
+
+    let mut push = Vec3::ZERO;
+    for fraction in PROBES {
+        let probe = position + velocity * lookahead * fraction;
+        let surface = surface_height(terrain, water_level, probe.xz());
+        let deficit = surface + clearance - probe.y;
+        if deficit <= 0.0 {
+            continue;
+        }
+
+        let slope = Vec2::new(
+            terrain.height(probe.xz() + Vec2::X * SLOPE_STEP) - terrain.height(probe.xz() - Vec2::X * SLOPE_STEP),
+            terrain.height(probe.xz() + Vec2::Y * SLOPE_STEP) - terrain.height(probe.xz() - Vec2::Y * SLOPE_STEP),
+        );
+        let downhill = -slope.normalize_or_zero();
+        // Nearer probes matter more
+        push += (Vec3::Y + Vec3::new(downhill.x, 0.0, downhill.y)) * (deficit / clearance.max(1.0)) / fraction;
+    }
+    push.normalize_or_zero()
+}
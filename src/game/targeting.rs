@@ -0,0 +1,327 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::game::{muzzle_position, EnemyArchetype, EnemyKind, EnemyMotion, EnemySet, GameState, PlayerFlight, RiverCourse, Turret, Velocity, Weapon, WeaponDef, WeaponSet};
+use crate::heightmap_material::TerrainSampler;
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Fire control for an armed enemy: how long it has had the player in sight
+/// and the warning it gives before each shot
+#[derive(Component, Clone, Debug, Default)]
+pub struct EnemyGunner {
+    /// Seconds the player has been in range and in sight
+    pub sighted: f32,
+    /// Seconds left before the telegraphed shot goes off
+    pub warning: Option<f32>,
+    /// Glow shown at the muzzle while warning
+    pub flash: Option<Entity>,
+    /// Keeps tracking but doesn't shoot, e.g. while a turret is still slewing round
+    pub hold_fire: bool,
+}
+
+/// Muzzle glow that grows until the shot it warns of is fired
+#[derive(Component)]
+pub struct TelegraphFlash {
+    /// Cancels out the scale of the enemy it is attached to
+    pub base_scale: Vec3,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct TargetingConfig {
+    /// Aim error in degrees for an archetype with zero accuracy
+    pub max_aim_error: f32,
+    /// Distance between terrain samples on a line of sight
+    pub sight_step: f32,
+}
+
+impl Default for TargetingConfig {
+    fn default() -> Self {
+        Self {
+            max_aim_error: 12.0,
+            sight_step: 6.0,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct TelegraphAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+/// Enemy aiming and fire decisions. Runs after enemies move and before weapons fire.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TargetingSet;
+
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TargetingConfig>()
+            .add_systems(Startup, create_telegraph_assets)
+            .add_systems(Update, (
+                aim_enemy_weapons,
+                update_telegraph_flashes,
+            ).chain().in_set(TargetingSet).after(EnemySet).before(WeaponSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Direction to fire a projectile at `speed` so it meets a target at `offset` from the
+/// muzzle moving at `relative_velocity` relative to the shooter. `None` if it can't catch up.
+pub fn intercept_direction(offset: Vec3, relative_velocity: Vec3, speed: f32) -> Option<Vec3> {
+    let time = intercept_time(offset, relative_velocity, speed)?;
+    (offset + relative_velocity * time).try_normalize()
+}
+
+/// Like `intercept_direction`, but for a projectile of `def`. One that speeds up is led
+/// with its average speed over the flight, refined a couple of times.
+pub fn lead_direction(offset: Vec3, relative_velocity: Vec3, def: &WeaponDef) -> Option<Vec3> {
+    if def.acceleration == 0.0 {
+        return intercept_direction(offset, relative_velocity, def.projectile_speed);
+    }
+    // A shot that can't catch up at muzzle speed may still do so once it has sped up
+    let mut time = intercept_time(offset, relative_velocity, def.projectile_speed).unwrap_or(def.lifetime);
+    for _ in 0..3 {
+        let average_speed = def.projectile_speed + 0.5 * def.acceleration * time.min(def.lifetime);
+        time = intercept_time(offset, relative_velocity, average_speed)?;
+    }
+    (offset + relative_velocity * time).try_normalize()
+}
+
+/// Seconds until a projectile at `speed` meets the target, see `intercept_direction`
+fn intercept_time(offset: Vec3, relative_velocity: Vec3, speed: f32) -> Option<f32> {
+    // |offset + relative_velocity * t| = speed * t
+    let a = relative_velocity.length_squared() - speed * speed;
+    let b = 2.0 * offset.dot(relative_velocity);
+    let c = offset.length_squared();
+
+    if a.abs() < 1e-4 {
+        // Target as fast as the projectile: only the linear term is left
+        return (b < 0.0).then(|| -c / b);
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+        .into_iter()
+        .filter(|&t| t > 0.0)
+        .min_by(f32::total_cmp)
+}
+
+/// Whether the terrain leaves a clear line from `from` to `to`, sampled every `step`
+pub fn line_of_sight(terrain: &TerrainSampler, from: Vec3, to: Vec3, step: f32) -> bool {
+    let steps = (from.distance(to) / step.max(0.1)).ceil() as usize;
+    // Skip the ends, which may sit right on the ground
+    (1..steps).all(|i| {
+        let point = from.lerp(to, i as f32 / steps as f32);
+        terrain.height(point.xz()) <= point.y
+    })
+}
+
+fn create_telegraph_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(TelegraphAssets {
+        mesh: meshes.add(Sphere::new(1.2)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(1.0, 0.35, 0.1),
+            emissive: LinearRgba::rgb(1.0, 0.35, 0.1) * 8.0,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+/// Leads the player, waits out the archetype's reaction time, then warns before each shot
+fn aim_enemy_weapons(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<TargetingConfig>,
+    course: Res<RiverCourse>,
+    telegraph_assets: Res<TelegraphAssets>,
+    archetypes: Res<Assets<EnemyArchetype>>,
+    defs: Res<Assets<WeaponDef>>,
+    players: Query<(&Transform, &Velocity), With<PlayerFlight>>,
+    mut enemies: Query<
+        (Entity, &mut Weapon, &mut EnemyGunner, &Transform, Option<&Velocity>, &EnemyKind, &EnemyMotion, Option<&Turret>),
+        Without<PlayerFlight>,
+    >,
+) {
+    let dt = time.delta_secs();
+    let player = players.single().ok();
+    let mut rng = rand::rng();
+
+    for (entity, mut weapon, mut gunner, transform, velocity, kind, motion, turret) in enemies.iter_mut() {
+        weapon.trigger_held = false;
+        let (Some(archetype), Some(def)) = (archetypes.get(&kind.0), defs.get(&weapon.def)) else {
+            continue;
+        };
+
+        let shooter_velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.0);
+        let aim = player.and_then(|(player, player_velocity)| {
+            // Shots leave from the muzzle, which sits off the hull towards the target
+            let muzzle = muzzle_position(transform, (player.translation - transform.translation).normalize_or(Vec3::Z));
+            let offset = player.translation - muzzle;
+            let ahead = course.0.distance_along(player.translation.xz()) < motion.along;
+            let visible = ahead
+                && offset.length() <= archetype.weapon_range
+                && line_of_sight(&course.0, transform.translation, player.translation, config.sight_step);
+            visible.then(|| {
+                lead_direction(offset, player_velocity.0 - shooter_velocity, def)
+                    .unwrap_or(offset.normalize_or(Vec3::Z))
+            })
+        });
+
+        let Some(aim) = aim else {
+            gunner.sighted = 0.0;
+            gunner.warning = None;
+            weapon.aim = None;
+            continue;
+        };
+        weapon.aim = Some(aim);
+        gunner.sighted += dt;
+        if gunner.sighted < archetype.reaction_time || weapon.cooldown > 0.0 || gunner.hold_fire {
+            continue;
+        }
+
+        let warning = gunner.warning.get_or_insert(archetype.telegraph);
+        *warning -= dt;
+        if *warning > 0.0 {
+            if gunner.flash.is_none() {
+                // Undo the model's scale so every flash is the same size
+                let base_scale = transform.scale.recip();
+                let flash = commands
+                    .spawn((
+                        TelegraphFlash { base_scale },
+                        Mesh3d(telegraph_assets.mesh.clone()),
+                        MeshMaterial3d(telegraph_assets.material.clone()),
+                        Transform::from_translation(Vec3::Z * archetype.radius * base_scale).with_scale(base_scale * 0.3),
+                        ChildOf(entity),
+                    ))
+                    .id();
+                gunner.flash = Some(flash);
+            }
+            continue;
+        }
+
+        // Turrets can only shoot where the barrel has swung to, which may lag the lead
+        let aim = turret.map_or(aim, |_| transform.rotation * Vec3::Z);
+        // Spread the shot by however inaccurate the archetype is
+        let error = (1.0 - archetype.accuracy.clamp(0.0, 1.0)) * config.max_aim_error.to_radians();
+        let axis = Quat::from_axis_angle(aim, rng.random_range(0.0..std::f32::consts::TAU)) * aim.any_orthonormal_vector();
+        weapon.aim = Some(Quat::from_axis_angle(axis, rng.random_range(0.0..=error)) * aim);
+        weapon.trigger_pulled = true;
+        gunner.warning = None;
+    }
+}
+
+/// Grows each warning glow towards the shot and clears it once fired
+fn update_telegraph_flashes(
+    mut commands: Commands,
+    archetypes: Res<Assets<EnemyArchetype>>,
+    mut gunners: Query<(&mut EnemyGunner, &EnemyKind)>,
+    mut flashes: Query<(&mut Transform, &TelegraphFlash)>,
+) {
+    for (mut gunner, kind) in gunners.iter_mut() {
+        let Some(flash) = gunner.flash else {
+            continue;
+        };
+        match gunner.warning {
+            Some(remaining) => {
+                let telegraph = archetypes.get(&kind.0).map_or(1.0, |archetype| archetype.telegraph.max(0.01));
+                if let Ok((mut transform, glow)) = flashes.get_mut(flash) {
+                    let growth = 0.3 + 0.7 * (1.0 - remaining / telegraph).clamp(0.0, 1.0);
+                    transform.scale = glow.base_scale * growth;
+                }
+            }
+            None => {
+                if let Ok(mut entity) = commands.get_entity(flash) {
+                    entity.despawn();
+                }
+                gunner.flash = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::WeaponLimit;
+
+    fn weapon(projectile_speed: f32, acceleration: f32) -> WeaponDef {
+        WeaponDef {
+            name: "test".into(),
+            slot: 0,
+            enemy: true,
+            fire_rate: 1.0,
+            automatic: false,
+            projectile_speed,
+            acceleration,
+            projectiles: 1,
+            spread: 0.0,
+            inaccuracy: 0.0,
+            damage: 1.0,
+            lifetime: 5.0,
+            radius: 0.5,
+            color: [1.0; 3],
+            limit: WeaponLimit::default(),
+        }
+    }
+
+    #[test]
+    fn stationary_target_is_shot_straight_at() {
+        let direction = intercept_direction(Vec3::new(0.0, 0.0, 50.0), Vec3::ZERO, 40.0).unwrap();
+        assert!(direction.distance(Vec3::Z) < 1e-5);
+    }
+
+    #[test]
+    fn target_as_fast_as_the_shot_is_met_when_approaching() {
+        let speed = 30.0;
+        let direction = intercept_direction(Vec3::X * 10.0, Vec3::NEG_X * speed, speed).unwrap();
+        assert!(direction.distance(Vec3::X) < 1e-5);
+    }
+
+    #[test]
+    fn target_as_fast_as_the_shot_is_missed_when_receding() {
+        let speed = 30.0;
+        assert!(intercept_direction(Vec3::X * 10.0, Vec3::X * speed, speed).is_none());
+    }
+
+    #[test]
+    fn target_outrunning_the_shot_is_missed() {
+        assert!(intercept_direction(Vec3::X * 10.0, Vec3::X * 20.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn crossing_target_is_led() {
+        let direction = intercept_direction(Vec3::new(0.0, 0.0, 100.0), Vec3::X * 10.0, 50.0).unwrap();
+        let time = (10_000.0f32 / 2_400.0).sqrt();
+        let expected = Vec3::new(10.0 * time, 0.0, 100.0).normalize();
+        assert!(direction.distance(expected) < 1e-4);
+    }
+
+    #[test]
+    fn constant_speed_lead_matches_the_intercept() {
+        let offset = Vec3::new(0.0, 0.0, 100.0);
+        let lead = lead_direction(offset, Vec3::X * 10.0, &weapon(50.0, 0.0)).unwrap();
+        assert_eq!(Some(lead), intercept_direction(offset, Vec3::X * 10.0, 50.0));
+    }
+
+    #[test]
+    fn accelerating_shot_leads_less() {
+        let offset = Vec3::new(0.0, 0.0, 100.0);
+        let constant = lead_direction(offset, Vec3::X * 10.0, &weapon(50.0, 0.0)).unwrap();
+        let boosted = lead_direction(offset, Vec3::X * 10.0, &weapon(50.0, 40.0)).unwrap();
+        assert!(boosted.x > 0.0 && boosted.x < constant.x);
+    }
+}
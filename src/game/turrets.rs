@@ -0,0 +1,301 @@
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+
+use crate::game::{EnemyGunner, EnemyMotion, EnemySet, GameState, InRun, Libraries, PlayerFlight, RiverCourse, TargetingSet, Weapon};
+use crate::heightmap_material::{GpuHeightmapConfigUI, GpuHeightmapRenderConfig, TerrainSampler, WaterLevelController};
+use crate::rendering::enemy::spawn_archetype_enemy;
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// Gun mount for an archetype that stays put. Angles in degrees.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TurretDef {
+    /// Furthest it swings either side of facing the river
+    pub yaw_limit: f32,
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    /// Degrees per second, for both yaw and pitch
+    pub turn_rate: f32,
+    /// Fires once the barrel is within this many degrees of the aim
+    pub tolerance: f32,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Turret {
+    pub def: TurretDef,
+    /// Facing at zero yaw and pitch, looking out over the river
+    pub base: Quat,
+    /// Radians
+    pub yaw: f32,
+    /// Radians, positive up
+    pub pitch: f32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource, Clone)]
+pub struct TurretConfig {
+    /// Mixed with the terrain seed, so the same terrain always gets the same sites
+    pub seed: u64,
+    /// Archetype names and how often each is picked
+    pub archetypes: Vec<(String, f32)>,
+    /// Length of river each batch of sites is placed for
+    pub segment_length: f32,
+    /// Spots tried per segment
+    pub candidates: u32,
+    /// Steepest ground a site is built on, in degrees
+    pub max_slope: f32,
+    /// How far back from the water's edge sites go
+    pub min_water_distance: f32,
+    pub max_water_distance: f32,
+    /// Least distance between two sites
+    pub min_spacing: f32,
+    /// How far ahead of the player sites are placed
+    pub spawn_ahead: f32,
+}
+
+impl Default for TurretConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0x7e44e7,
+            archetypes: vec![("AA Gun".into(), 2.0), ("SAM Site".into(), 1.0)],
+            segment_length: 250.0,
+            candidates: 6,
+            max_slope: 14.0,
+            min_water_distance: 10.0,
+            max_water_distance: 45.0,
+            min_spacing: 90.0,
+            spawn_ahead: 600.0,
+        }
+    }
+}
+
+/// Placement progress for the current run
+#[derive(Resource, Default)]
+pub struct TurretPlacement {
+    next_segment: i64,
+    /// Sites in the last couple of segments, for the spacing check
+    recent: Vec<(f32, Vec2)>,
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct TurretPlugin;
+
+impl Plugin for TurretPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TurretConfig>()
+            .init_resource::<TurretPlacement>()
+            .add_systems(OnEnter(InRun), reset_turret_placement)
+            .add_systems(Update, place_turrets.before(EnemySet).run_if(in_state(GameState::Playing)))
+            .add_systems(Update, aim_turrets.after(EnemySet).before(TargetingSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn reset_turret_placement(
+    mut placement: ResMut<TurretPlacement>,
+    config: Res<TurretConfig>,
+    players: Query<&PlayerFlight>,
+) {
+    let along = players.single().map_or(0.0, |flight| flight.along);
+    *placement = TurretPlacement {
+        next_segment: (along / config.segment_length.max(1.0)).floor() as i64 + 1,
+        recent: Vec::new(),
+    };
+}
+
+/// Picks sites for one segment of river. Each segment has its own generator, so the
+/// result doesn't depend on frame timing, only on the seed.
+fn segment_sites(
+    config: &TurretConfig,
+    seed: u64,
+    segment: i64,
+    terrain: &TerrainSampler,
+    water_level: f32,
+    recent: &mut Vec<(f32, Vec2)>,
+) -> Vec<(f32, f32, Vec2)> {
+    const SLOPE_STEP: f32 = 2.0;
+    const EDGE_STEP: f32 = 2.0;
+
+    let mut rng = StdRng::seed_from_u64(seed ^ (segment as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    let max_gradient = config.max_slope.to_radians().tan();
+    let start = segment as f32 * config.segment_length;
+    let mut sites = Vec::new();
+
+    for _ in 0..config.candidates {
+        let along = start + rng.random_range(0.0..config.segment_length);
+        let side = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+        let back = rng.random_range(config.min_water_distance..=config.max_water_distance.max(config.min_water_distance));
+
+        // Walk out from the channel until the ground is dry
+        let centre = terrain.meander(along);
+        let mut edge = terrain.river_width_at(along) * 0.25;
+        while edge < terrain.river_width_at(along) * 2.0
+            && terrain.height(terrain.axis_point(along, centre + side * edge)) <= water_level
+        {
+            edge += EDGE_STEP;
+        }
+        let lateral = side * (edge + back);
+        let xz = terrain.axis_point(along, centre + lateral);
+
+        if terrain.height(xz) <= water_level + 0.5 {
+            continue;
+        }
+        let gradient = Vec2::new(
+            terrain.height(xz + Vec2::X * SLOPE_STEP) - terrain.height(xz - Vec2::X * SLOPE_STEP),
+            terrain.height(xz + Vec2::Y * SLOPE_STEP) - terrain.height(xz - Vec2::Y * SLOPE_STEP),
+        ) / (2.0 * SLOPE_STEP);
+        if gradient.length() > max_gradient {
+            continue;
+        }
+        if recent.iter().any(|&(_, other)| other.distance(xz) < config.min_spacing) {
+            continue;
+        }
+
+        recent.push((along, xz));
+        sites.push((along, lateral, xz));
+    }
+    sites
+}
+
+/// Lays down turrets on the banks ahead of the player, a segment at a time
+fn place_turrets(
+    mut commands: Commands,
+    config: Res<TurretConfig>,
+    mut placement: ResMut<TurretPlacement>,
+    course: Res<RiverCourse>,
+    terrain_config: Res<GpuHeightmapConfigUI>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    water_level: Option<Res<WaterLevelController>>,
+    libraries: Libraries,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    players: Query<&PlayerFlight>,
+) {
+    let Ok(flight) = players.single() else {
+        return;
+    };
+    let kinds: Vec<_> = config
+        .archetypes
+        .iter()
+        .filter_map(|(name, weight)| libraries.enemy(name).map(|(handle, archetype)| (handle, archetype, *weight)))
+        .collect();
+    // Wait for every archetype so a slow load can't change what gets placed
+    if kinds.len() < config.archetypes.len() {
+        return;
+    }
+    let total_weight: f32 = kinds.iter().map(|(_, _, weight)| weight.max(0.0)).sum();
+    if total_weight <= 0.0 {
+        return;
+    }
+
+    let course = &course.0;
+    // A tide or keyframes would make the sites depend on when each segment streams in
+    let water_level = water_level
+        .and_then(|controller| controller.mode.base_level())
+        .unwrap_or(render_config.water_level_offset);
+    let seed = config.seed ^ u64::from(terrain_config.seed.to_bits());
+    let segment_length = config.segment_length.max(1.0);
+
+    while (placement.next_segment as f32) * segment_length < flight.along + config.spawn_ahead {
+        let segment = placement.next_segment;
+        placement.next_segment += 1;
+        let mut recent = std::mem::take(&mut placement.recent);
+        recent.retain(|&(along, _)| along > (segment - 1) as f32 * segment_length - config.min_spacing);
+        let sites = segment_sites(&config, seed, segment, course, water_level, &mut recent);
+        placement.recent = recent;
+
+        let mut rng = StdRng::seed_from_u64(seed.rotate_left(17) ^ segment as u64);
+        for (along, lateral, xz) in sites {
+            let mut pick = rng.random_range(0.0..total_weight);
+            let Some((handle, archetype, _)) = kinds.iter().find(|(_, _, weight)| {
+                pick -= weight.max(0.0);
+                pick < 0.0
+            }) else {
+                continue;
+            };
+            let Some(def) = archetype.turret else {
+                warn!("Turret archetype '{}' has no turret mount", archetype.name);
+                continue;
+            };
+
+            let side = lateral.signum();
+            let motion = EnemyMotion::new(archetype.movement, 0.0, along, lateral, side);
+            let enemy = spawn_archetype_enemy(
+                &mut commands,
+                &asset_server,
+                &mut meshes,
+                &mut materials,
+                handle.clone(),
+                archetype,
+                motion,
+            );
+
+            // Face out over the river
+            let centre = course.river_center(along);
+            let facing = Vec3::new(centre.x - xz.x, 0.0, centre.y - xz.y).normalize_or(Vec3::Z);
+            let base = Quat::from_rotation_arc(Vec3::Z, facing);
+            let scale = if archetype.model.is_some() { archetype.model_scale } else { 1.0 };
+            let height = course.height(xz) + archetype.radius * 0.5;
+            commands.entity(enemy).insert((
+                Transform::from_xyz(xz.x, height, xz.y)
+                    .with_rotation(base)
+                    .with_scale(Vec3::splat(scale)),
+                Turret {
+                    def,
+                    base,
+                    yaw: 0.0,
+                    pitch: 0.0,
+                },
+            ));
+        }
+    }
+}
+
+/// Swings each turret towards its weapon's aim within its limits, and holds fire
+/// until it is pointing close enough
+fn aim_turrets(
+    time: Res<Time>,
+    players: Query<&Transform, (With<PlayerFlight>, Without<Turret>)>,
+    mut turrets: Query<(&mut Turret, &mut Transform, &Weapon, &mut EnemyGunner)>,
+) {
+    let dt = time.delta_secs();
+    let player = players.single().ok();
+
+    for (mut turret, mut transform, weapon, mut gunner) in turrets.iter_mut() {
+        let def = turret.def;
+        // Last frame's lead if there is one, otherwise straight at the player
+        let aim = weapon
+            .aim
+            .or_else(|| player.map(|player| player.translation - transform.translation))
+            .and_then(Vec3::try_normalize);
+        let Some(aim) = aim else {
+            gunner.hold_fire = true;
+            continue;
+        };
+
+        // Aim in the mount's own frame: yaw about its up, pitch up from level
+        let local = turret.base.inverse() * aim;
+        let wanted_yaw = local.x.atan2(local.z);
+        let wanted_pitch = local.y.clamp(-1.0, 1.0).asin();
+        let yaw_limit = def.yaw_limit.to_radians();
+        let (min_pitch, max_pitch) = (def.min_pitch.to_radians(), def.max_pitch.to_radians());
+        let reachable = wanted_yaw.abs() <= yaw_limit && (min_pitch..=max_pitch).contains(&wanted_pitch);
+
+        let step = def.turn_rate.to_radians() * dt;
+        let yaw_target = wanted_yaw.clamp(-yaw_limit, yaw_limit);
+        let pitch_target = wanted_pitch.clamp(min_pitch, max_pitch);
+        turret.yaw += (yaw_target - turret.yaw).clamp(-step, step);
+        turret.pitch += (pitch_target - turret.pitch).clamp(-step, step);
+
+        // Nose is +Z, so a positive pitch is a negative turn about +X
+        transform.rotation = turret.base * Quat::from_rotation_y(turret.yaw) * Quat::from_rotation_x(-turret.pitch);
+        let pointing = transform.rotation * Vec3::Z;
+        gunner.hold_fire = !reachable || pointing.angle_between(aim) > def.tolerance.to_radians();
+    }
+}
@@ -0,0 +1,428 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::data::RonAssetPlugin;
+use crate::game::{boss_position, spawn_boss, spawn_formation, Boss, EnemyMotion, EnemySet, Formation, FormationBreak, FormationShape, GameState, Health, InRun, Libraries, PlayerFlight, RiverCourse, Steering, SteeringDef};
+use crate::heightmap_material::{GpuHeightmapRenderConfig, TerrainSampler};
+use crate::paths::{CurveKind, MeasuredPath, PathEnd, PathFollower, PathShape, SpeedProfile};
+use crate::rendering::enemy::spawn_archetype_enemy;
+
+const WAVE_SCRIPT: &str = "waves/river.waves.ron";
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// When a wave arrives, measured from the moment the previous wave was sent
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum WaveTrigger {
+    /// Seconds of play
+    Time(f32),
+    /// Distance flown along the river
+    Distance(f32),
+    /// As soon as nothing from earlier waves is left
+    Cleared,
+}
+
+/// How a wave's enemies are laid out
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum SpawnRule {
+    /// Single file down the middle of the channel, `spacing` apart
+    #[default]
+    Column,
+    /// Side by side across the channel, `spacing` apart
+    Abreast,
+    /// Alternating banks, `spacing` apart along the river
+    Banks,
+    /// Random offsets, roughly `spacing` apart along the river
+    Scatter,
+    /// Fly a path instead of the archetype's movement, one after another `spacing` apart.
+    /// Points are (along, lateral, height): distance past the wave's start (negative is back
+    /// towards the player), offset from the river's centre line and height above the ground or water.
+    Path {
+        #[serde(default)]
+        curve: CurveKind,
+        points: Vec<[f32; 3]>,
+        #[serde(default)]
+        end: PathEnd,
+        /// World units per second; the archetype's speed if unset
+        #[serde(default)]
+        speed: Option<f32>,
+        /// (fraction of the path, speed multiplier) pairs
+        #[serde(default)]
+        speed_profile: SpeedProfile,
+    },
+    /// Fly a path generated from the terrain, one after another `spacing` apart
+    Generated {
+        shape: PathShape,
+        /// Least height kept above the ground or water
+        #[serde(default = "default_clearance")]
+        clearance: f32,
+        #[serde(default)]
+        end: PathEnd,
+        #[serde(default)]
+        speed: Option<f32>,
+        #[serde(default)]
+        speed_profile: SpeedProfile,
+    },
+}
+
+fn default_clearance() -> f32 {
+    8.0
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct WaveDef {
+    pub name: String,
+    pub trigger: WaveTrigger,
+    /// Archetype name, see `assets/enemies`
+    #[serde(default)]
+    pub enemy: String,
+    #[serde(default)]
+    pub count: u32,
+    #[serde(default)]
+    pub spacing: f32,
+    #[serde(default)]
+    pub spawn: SpawnRule,
+    /// Boss name, see `assets/bosses`. Sent on its own; the enemy fields are ignored.
+    #[serde(default)]
+    pub boss: Option<String>,
+    /// Distance ahead of the player the first enemy is placed
+    #[serde(default = "default_ahead")]
+    pub ahead: f32,
+    /// With a path spawn rule, fly the path together instead of one after another
+    #[serde(default)]
+    pub formation: Option<FormationDef>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct FormationDef {
+    pub shape: FormationShape,
+    pub spacing: f32,
+    #[serde(default)]
+    pub break_on: Option<FormationBreak>,
+}
+
+fn default_ahead() -> f32 {
+    350.0
+}
+
+/// Added on each pass through a looping script
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[serde(default)]
+pub struct WaveEscalation {
+    /// Fraction of extra health per lap
+    pub health: f32,
+    /// Fraction of extra speed per lap
+    pub speed: f32,
+    /// Extra enemies per wave per lap
+    pub count: u32,
+}
+
+/// Wave list loaded from `assets/waves/*.waves.ron`
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct WaveScript {
+    pub waves: Vec<WaveDef>,
+    /// Wave to go back to after the last one; the script stops if unset
+    #[serde(default)]
+    pub loop_from: Option<usize>,
+    #[serde(default)]
+    pub escalation: WaveEscalation,
+}
+
+/* ------------------------------- Events ------------------------------- */
+
+/// Every enemy of a wave has been destroyed or left behind
+#[derive(Event, Clone, Debug)]
+pub struct WaveCleared {
+    pub name: String,
+    /// Passes completed through the script when the wave was sent
+    pub lap: u32,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Which wave an enemy was sent in
+#[derive(Component, Clone, Copy, Debug)]
+pub struct WaveMember {
+    pub wave: u32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+/// Wave sent and still being tracked for clearing
+#[derive(Clone, Debug)]
+struct ActiveWave {
+    id: u32,
+    name: String,
+    lap: u32,
+}
+
+/// Progress through the wave script for the current run
+#[derive(Resource)]
+pub struct WaveDirector {
+    script: Handle<WaveScript>,
+    /// Index of the next wave to send; `None` once a non-looping script has run out
+    next: Option<usize>,
+    lap: u32,
+    /// Play time and distance when the previous wave was sent
+    last_sent_time: f32,
+    last_sent_along: f32,
+    elapsed: f32,
+    next_id: u32,
+    active: Vec<ActiveWave>,
+}
+
+impl WaveDirector {
+    fn new(script: Handle<WaveScript>) -> Self {
+        Self {
+            script,
+            next: Some(0),
+            lap: 0,
+            last_sent_time: 0.0,
+            last_sent_along: 0.0,
+            elapsed: 0.0,
+            next_id: 0,
+            active: Vec::new(),
+        }
+    }
+
+    /// Starts tracking a wave that has just been sent and returns its id
+    fn track(&mut self, name: &str, lap: u32) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.active.push(ActiveWave {
+            id,
+            name: name.to_string(),
+            lap,
+        });
+        id
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<WaveScript>::new(&["waves.ron"]))
+            .add_event::<WaveCleared>()
+            .add_systems(Startup, load_wave_script)
+            .add_systems(OnEnter(InRun), reset_waves)
+            .add_systems(Update, (
+                send_waves,
+                track_cleared_waves,
+                announce_cleared_waves,
+            ).chain().before(EnemySet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn load_wave_script(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WaveDirector::new(asset_server.load(WAVE_SCRIPT)));
+}
+
+fn reset_waves(mut director: ResMut<WaveDirector>) {
+    let script = director.script.clone();
+    *director = WaveDirector::new(script);
+}
+
+fn send_waves(
+    mut commands: Commands,
+    time: Res<Time>,
+    scripts: Res<Assets<WaveScript>>,
+    libraries: Libraries,
+    course: Res<RiverCourse>,
+    render_config: Res<GpuHeightmapRenderConfig>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut director: ResMut<WaveDirector>,
+    players: Query<&PlayerFlight>,
+) {
+    director.elapsed += time.delta_secs();
+    let (Some(script), Ok(flight)) = (scripts.get(&director.script), players.single()) else {
+        return;
+    };
+    if !libraries.has_enemies() {
+        return;
+    }
+    let Some(index) = director.next else {
+        return;
+    };
+    let Some(wave) = script.waves.get(index) else {
+        director.next = None;
+        return;
+    };
+
+    let ready = match wave.trigger {
+        WaveTrigger::Time(seconds) => director.elapsed - director.last_sent_time >= seconds,
+        WaveTrigger::Distance(distance) => flight.along - director.last_sent_along >= distance,
+        WaveTrigger::Cleared => director.active.is_empty(),
+    };
+    if !ready {
+        return;
+    }
+
+    director.last_sent_time = director.elapsed;
+    director.last_sent_along = flight.along;
+    director.next = if index + 1 < script.waves.len() {
+        Some(index + 1)
+    } else {
+        script.loop_from.filter(|&from| from < script.waves.len())
+    };
+    let lap = director.lap;
+    if director.next.is_some_and(|next| next <= index) {
+        director.lap += 1;
+    }
+
+    let escalation = script.escalation;
+    let water_level = render_config.water_level_offset;
+
+    if let Some(name) = &wave.boss {
+        let Some((handle, def)) = libraries.boss(name) else {
+            warn!("Wave '{}' names unknown boss '{}'", wave.name, name);
+            return;
+        };
+        let id = director.track(&wave.name, lap);
+        let boss = Boss::new(handle, def, wave.ahead, 1.0 + escalation.health * lap as f32);
+        let height = def.phases.first().map_or(0.0, |phase| phase.movement.height);
+        let position = boss_position(&course.0, water_level, flight.along + wave.ahead, 0.0, height);
+        let entity = spawn_boss(&mut commands, &mut meshes, &mut materials, def, boss, position);
+        commands.entity(entity).insert(WaveMember { wave: id });
+        return;
+    }
+
+    let Some((handle, archetype)) = libraries.enemy(&wave.enemy) else {
+        warn!("Wave '{}' names unknown enemy '{}'", wave.name, wave.enemy);
+        return;
+    };
+
+    let count = wave.count + escalation.count * lap;
+    let health = archetype.health * (1.0 + escalation.health * lap as f32);
+    let speed_scale = 1.0 + escalation.speed * lap as f32;
+    let speed = archetype.speed * speed_scale;
+
+    let id = director.track(&wave.name, lap);
+
+    let mut rng = rand::rng();
+    let course = &course.0;
+    let first = flight.along + wave.ahead;
+    let mut members = Vec::new();
+    let mut formation_follower = None;
+    for i in 0..count {
+        let row = i as f32 * wave.spacing;
+        let (along, offset, side) = match &wave.spawn {
+            SpawnRule::Column => (first + row, 0.0, 1.0),
+            SpawnRule::Abreast => {
+                let offset = (i as f32 - (count - 1) as f32 * 0.5) * wave.spacing;
+                (first, offset, if offset < 0.0 { -1.0 } else { 1.0 })
+            }
+            SpawnRule::Banks => (first + row, 0.0, if i % 2 == 0 { -1.0 } else { 1.0 }),
+            SpawnRule::Scatter => {
+                let half_width = course.river_width_at(first + row) * 0.5;
+                let along = first + row + rng.random_range(-0.25..=0.25) * wave.spacing;
+                let side = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+                (along, rng.random_range(-0.6..=0.6) * half_width, side)
+            }
+            SpawnRule::Path { .. } | SpawnRule::Generated { .. } => (first + row, 0.0, 1.0),
+        };
+
+        let motion = EnemyMotion::new(archetype.movement, speed, along, offset, side);
+        let enemy = spawn_archetype_enemy(
+            &mut commands,
+            &asset_server,
+            &mut meshes,
+            &mut materials,
+            handle.clone(),
+            archetype,
+            motion,
+        );
+        commands.entity(enemy).insert((Health::new(health), WaveMember { wave: id }));
+        if let Some(steering) = &archetype.steering {
+            // Steered enemies fly at their own top speed rather than the motion's
+            commands.entity(enemy).insert(Steering::new(SteeringDef {
+                max_speed: steering.max_speed * speed_scale,
+                ..steering.clone()
+            }));
+        }
+
+        if wave.formation.is_some() {
+            // One leader flies the path for the whole wave
+            if formation_follower.is_none() {
+                formation_follower = route_follower(&wave.spawn, course, water_level, first, speed, speed_scale, archetype.banks);
+            }
+            if formation_follower.is_some() {
+                members.push(enemy);
+            }
+        } else if let Some(follower) = route_follower(&wave.spawn, course, water_level, along, speed, speed_scale, archetype.banks) {
+            commands.entity(enemy).insert(follower);
+        }
+    }
+
+    if let (Some(def), Some(follower)) = (wave.formation, formation_follower) {
+        let formation = Formation::new(def.shape, def.spacing, members, def.break_on);
+        spawn_formation(&mut commands, formation, follower);
+    }
+}
+
+/// The follower for an enemy sent along a path rule starting `along` the river,
+/// or `None` for rules that leave it to the archetype's movement
+fn route_follower(
+    rule: &SpawnRule,
+    course: &TerrainSampler,
+    water_level: f32,
+    along: f32,
+    speed: f32,
+    speed_scale: f32,
+    banks: bool,
+) -> Option<PathFollower> {
+    let (path, end, path_speed, speed_profile) = match rule {
+        SpawnRule::Path { curve, points, end, speed, speed_profile } => {
+            let world_points = points
+                .iter()
+                .map(|&[point_along, lateral, height]| {
+                    let along = along + point_along;
+                    let xz = course.axis_point(along, course.meander(along) + lateral);
+                    Vec3::new(xz.x, course.height(xz).max(water_level) + height, xz.y)
+                })
+                .collect();
+            (curve.build(world_points), end, speed, speed_profile)
+        }
+        SpawnRule::Generated { shape, clearance, end, speed, speed_profile } => {
+            (shape.build(course, water_level, along, *clearance), end, speed, speed_profile)
+        }
+        _ => return None,
+    };
+
+    let speed = path_speed.map_or(speed, |path_speed| path_speed * speed_scale);
+    let follower = PathFollower::new(Arc::new(MeasuredPath::new(path)), speed, *end).with_profile(speed_profile.clone());
+    Some(if banks { follower } else { follower.without_banking() })
+}
+
+fn track_cleared_waves(
+    mut director: ResMut<WaveDirector>,
+    members: Query<&WaveMember>,
+    mut cleared: EventWriter<WaveCleared>,
+) {
+    if director.active.is_empty() {
+        return;
+    }
+    director.active.retain(|wave| {
+        let remaining = members.iter().any(|member| member.wave == wave.id);
+        if !remaining {
+            cleared.write(WaveCleared {
+                name: wave.name.clone(),
+                lap: wave.lap,
+            });
+        }
+        remaining
+    });
+}
+
+fn announce_cleared_waves(mut cleared: EventReader<WaveCleared>) {
+    for wave in cleared.read() {
+        info!("🌊 Wave '{}' cleared (lap {})", wave.name, wave.lap + 1);
+    }
+}
@@ -0,0 +1,307 @@
+use bevy::asset::LoadedFolder;
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::data::RonAssetPlugin;
+use crate::game::{CollisionLayer, FlightSet, GameState, PlayerFlight, Velocity};
+use crate::rendering::bullet::{spawn_bullet, BulletAssets};
+
+const WEAPON_FOLDER: &str = "weapons";
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// What stops a weapon firing forever
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum WeaponLimit {
+    #[default]
+    Unlimited,
+    /// Shots carried per life
+    Ammo(u32),
+    /// Each shot adds `per_shot` heat, `cooling` drains per second.
+    /// At 1.0 the weapon locks until it has cooled right down.
+    Heat { per_shot: f32, cooling: f32 },
+}
+
+/// Weapon stats loaded from `assets/weapons/*.weapon.ron`
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct WeaponDef {
+    pub name: String,
+    /// Position in the loadout; slot 1 is selected with the 1 key
+    #[serde(default)]
+    pub slot: u32,
+    /// Only enemies carry it; left out of the player's loadout
+    #[serde(default)]
+    pub enemy: bool,
+    /// Shots per second
+    pub fire_rate: f32,
+    /// Keeps firing while the trigger is held
+    #[serde(default)]
+    pub automatic: bool,
+    /// Muzzle speed, added on top of the shooter's velocity
+    pub projectile_speed: f32,
+    /// Extra speed gained per second of flight
+    #[serde(default)]
+    pub acceleration: f32,
+    #[serde(default = "default_projectiles")]
+    pub projectiles: u32,
+    /// Width in degrees of the fan the projectiles of one shot are spread over
+    #[serde(default)]
+    pub spread: f32,
+    /// Random aim error per projectile, in degrees
+    #[serde(default)]
+    pub inaccuracy: f32,
+    pub damage: f32,
+    pub lifetime: f32,
+    pub radius: f32,
+    /// sRGB
+    pub color: [f32; 3],
+    #[serde(default)]
+    pub limit: WeaponLimit,
+}
+
+fn default_projectiles() -> u32 {
+    1
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Equipped weapon. Whoever controls the shooter sets the trigger flags,
+/// `fire_weapons` does the rest and releases `trigger_pulled`.
+#[derive(Component, Clone, Debug)]
+pub struct Weapon {
+    pub def: Handle<WeaponDef>,
+    pub projectile_layer: CollisionLayer,
+    /// World direction to fire in; `None` fires down the shooter's nose
+    pub aim: Option<Vec3>,
+    /// Seconds until the next shot is ready
+    pub cooldown: f32,
+    /// `None` for weapons without an ammo limit
+    pub ammo: Option<u32>,
+    pub heat: f32,
+    pub overheated: bool,
+    pub trigger_held: bool,
+    pub trigger_pulled: bool,
+}
+
+impl Weapon {
+    pub fn new(handle: Handle<WeaponDef>, def: &WeaponDef, projectile_layer: CollisionLayer) -> Self {
+        Self {
+            def: handle,
+            projectile_layer,
+            aim: None,
+            cooldown: 0.0,
+            ammo: match def.limit {
+                WeaponLimit::Ammo(ammo) => Some(ammo),
+                _ => None,
+            },
+            heat: 0.0,
+            overheated: false,
+            trigger_held: false,
+            trigger_pulled: false,
+        }
+    }
+}
+
+/// Every weapon the player carries, ordered by slot. The entry at `selected`
+/// is only brought up to date when the player switches away from it.
+#[derive(Component, Clone, Debug)]
+pub struct WeaponLoadout {
+    pub weapons: Vec<Weapon>,
+    pub selected: usize,
+}
+
+impl WeaponLoadout {
+    /// Stows the equipped weapon and equips slot `index`, keeping ammo and heat per weapon
+    pub fn select(&mut self, equipped: &mut Weapon, index: usize) {
+        if index == self.selected || index >= self.weapons.len() {
+            return;
+        }
+        self.weapons[self.selected] = equipped.clone();
+        *equipped = self.weapons[index].clone();
+        equipped.trigger_held = false;
+        equipped.trigger_pulled = false;
+        self.selected = index;
+    }
+
+    pub fn select_next(&mut self, equipped: &mut Weapon) {
+        if !self.weapons.is_empty() {
+            self.select(equipped, (self.selected + 1) % self.weapons.len());
+        }
+    }
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+/// All weapon definitions found in the weapon folder
+#[derive(Resource)]
+pub struct WeaponLibrary {
+    folder: Handle<LoadedFolder>,
+}
+
+impl WeaponLibrary {
+    /// Loaded definitions, sorted by slot
+    pub fn weapons<'a>(
+        &self,
+        folders: &Assets<LoadedFolder>,
+        defs: &'a Assets<WeaponDef>,
+    ) -> Vec<(Handle<WeaponDef>, &'a WeaponDef)> {
+        let Some(folder) = folders.get(&self.folder) else {
+            return Vec::new();
+        };
+        let mut found: Vec<(Handle<WeaponDef>, &WeaponDef)> = folder
+            .handles
+            .iter()
+            .filter_map(|handle| handle.clone().try_typed::<WeaponDef>().ok())
+            .filter_map(|handle| defs.get(&handle).map(|def| (handle, def)))
+            .collect();
+        found.sort_by_key(|(_, def)| def.slot);
+        found
+    }
+
+    pub fn find<'a>(
+        &self,
+        name: &str,
+        folders: &Assets<LoadedFolder>,
+        defs: &'a Assets<WeaponDef>,
+    ) -> Option<(Handle<WeaponDef>, &'a WeaponDef)> {
+        self.weapons(folders, defs)
+            .into_iter()
+            .find(|(_, def)| def.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+/// Weapon cooldowns and firing; trigger input should run before this
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WeaponSet;
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<WeaponDef>::new(&["weapon.ron"]))
+            .add_systems(Startup, load_weapon_defs)
+            .add_systems(Update, (
+                equip_player_weapons,
+                fire_weapons,
+            ).chain().in_set(WeaponSet).after(FlightSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn load_weapon_defs(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WeaponLibrary {
+        folder: asset_server.load_folder(WEAPON_FOLDER),
+    });
+}
+
+/// Hands a full loadout to a freshly spawned plane once the definitions are loaded
+fn equip_player_weapons(
+    mut commands: Commands,
+    library: Res<WeaponLibrary>,
+    folders: Res<Assets<LoadedFolder>>,
+    defs: Res<Assets<WeaponDef>>,
+    players: Query<Entity, (With<PlayerFlight>, Without<WeaponLoadout>)>,
+) {
+    if players.is_empty() {
+        return;
+    }
+    let weapons: Vec<Weapon> = library
+        .weapons(&folders, &defs)
+        .into_iter()
+        .filter(|(_, def)| !def.enemy)
+        .map(|(handle, def)| Weapon::new(handle, def, CollisionLayer::PlayerBullet))
+        .collect();
+    let Some(first) = weapons.first().cloned() else {
+        return;
+    };
+
+    for entity in players.iter() {
+        commands.entity(entity).insert((
+            first.clone(),
+            WeaponLoadout {
+                weapons: weapons.clone(),
+                selected: 0,
+            },
+        ));
+    }
+}
+
+/// Where shots fired along `forward` leave a shooter: ahead of and just under the hull
+pub fn muzzle_position(transform: &Transform, forward: Vec3) -> Vec3 {
+    transform.translation + forward * 4.0 - Vec3::Y
+}
+
+fn fire_weapons(
+    mut commands: Commands,
+    time: Res<Time>,
+    defs: Res<Assets<WeaponDef>>,
+    mut bullet_assets: ResMut<BulletAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut shooters: Query<(&mut Weapon, &Transform, Option<&Velocity>)>,
+) {
+    let dt = time.delta_secs();
+    let mut rng = rand::rng();
+
+    for (mut weapon, transform, velocity) in shooters.iter_mut() {
+        let pulled = std::mem::take(&mut weapon.trigger_pulled);
+        let Some(def) = defs.get(&weapon.def) else {
+            continue;
+        };
+
+        weapon.cooldown = (weapon.cooldown - dt).max(0.0);
+        if let WeaponLimit::Heat { cooling, .. } = def.limit {
+            weapon.heat = (weapon.heat - cooling * dt).max(0.0);
+            if weapon.heat <= 0.0 {
+                weapon.overheated = false;
+            }
+        }
+
+        let wants_to_fire = pulled || (def.automatic && weapon.trigger_held);
+        if !wants_to_fire || weapon.cooldown > 0.0 || weapon.overheated || weapon.ammo == Some(0) {
+            continue;
+        }
+
+        weapon.cooldown = 1.0 / def.fire_rate.max(0.01);
+        if let Some(ammo) = weapon.ammo.as_mut() {
+            *ammo -= 1;
+        }
+        if let WeaponLimit::Heat { per_shot, .. } = def.limit {
+            weapon.heat += per_shot;
+            weapon.overheated = weapon.heat >= 1.0;
+        }
+
+        // Nose is local +Z; fan the shot out around the vertical
+        let forward = weapon
+            .aim
+            .and_then(Vec3::try_normalize)
+            .unwrap_or(transform.rotation * Vec3::Z);
+        let muzzle = muzzle_position(transform, forward);
+        let shooter_velocity = velocity.map_or(Vec3::ZERO, |velocity| velocity.0);
+        let look = bullet_assets.look(weapon.def.id(), def, &mut meshes, &mut materials);
+        let count = def.projectiles.max(1);
+        for i in 0..count {
+            let fan = if count > 1 {
+                (i as f32 / (count - 1) as f32 - 0.5) * def.spread
+            } else {
+                0.0
+            };
+            let error = def.inaccuracy * 0.5;
+            let yaw = (fan + rng.random_range(-error..=error)).to_radians();
+            let pitch = rng.random_range(-error..=error).to_radians();
+            let right = forward.cross(Vec3::Y).try_normalize().unwrap_or(Vec3::X);
+            let direction = Quat::from_axis_angle(Vec3::Y, yaw) * Quat::from_axis_angle(right, pitch) * forward;
+
+            spawn_bullet(
+                &mut commands,
+                look.clone(),
+                def,
+                weapon.projectile_layer,
+                muzzle,
+                shooter_velocity + direction * def.projectile_speed,
+            );
+        }
+    }
+}
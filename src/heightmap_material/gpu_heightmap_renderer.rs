@@ -1,9 +1,10 @@
-use bevy::{log, prelude::*};
+use bevy::prelude::*;
 use bevy::render::mesh::{Indices, PrimitiveTopology};
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy_egui::EguiPrimaryContextPass;
 
-use crate::heightmap_material::{CompleteGpuHeightmapMaterial, CompleteMaskedRiverWaterMaterial, GpuHeightmapMaterial};
+use crate::game::EditorUiSet;
+use crate::heightmap_material::{CompleteGpuHeightmapMaterial, CompleteMaskedRiverWaterMaterial, GpuHeightmapMaterial, WaterLevelChanged};
 
 #[derive(Component)]
 pub struct GpuHeightmapTerrain;
@@ -20,11 +21,6 @@ pub struct GpuHeightmapRenderConfig {
     pub enable_water_rendering: bool,
 }
 
-#[derive(Resource, Default)]
-pub struct LastWaterLevelOffset {
-    offset: f32,
-}
-
 #[derive(Resource, Default)]
 pub struct GpuTerrainState {
     pub terrain_entity: Option<Entity>,
@@ -48,8 +44,8 @@ impl Plugin for GpuHeightmapRendererPlugin {
         app
             .init_resource::<GpuHeightmapRenderConfig>()
             .init_resource::<GpuTerrainState>()
-            .init_resource::<LastWaterLevelOffset>()
-            .add_systems(EguiPrimaryContextPass, gpu_heightmap_render_ui)
+            .add_event::<WaterLevelChanged>()
+            .add_systems(EguiPrimaryContextPass, gpu_heightmap_render_ui.in_set(EditorUiSet))
             .add_systems(Update, (
                 update_water_level_on_change,
             ));
@@ -118,7 +114,7 @@ pub fn gpu_heightmap_render_ui(
         });
 }
 
-fn render_gpu_terrain(
+pub(crate) fn render_gpu_terrain(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     terrain_materials: &mut ResMut<Assets<CompleteGpuHeightmapMaterial>>,
@@ -200,7 +196,10 @@ fn setup_water(
 ) {
     let water_mesh = create_water_plane_mesh(config);
     
-    let water_material = CompleteMaskedRiverWaterMaterial::default();
+    let mut water_material = CompleteMaskedRiverWaterMaterial::default();
+    // Visible from below for the underwater camera
+    water_material.base.cull_mode = None;
+    water_material.base.double_sided = true;
 
     commands.spawn((
         Mesh3d(meshes.add(water_mesh)),
@@ -330,23 +329,25 @@ fn clear_gpu_terrain(
     info!("GPU terrain cleared.");
 }
 
-fn update_water_level_on_change(
+pub(crate) fn update_water_level_on_change(
     render_config: Res<GpuHeightmapRenderConfig>,
-    mut last_offset: ResMut<LastWaterLevelOffset>,
+    mut level_changed: EventReader<WaterLevelChanged>,
     mut water_query: Query<&mut Transform, With<GpuHeightmapWater>>,
 ) {
-    let offset_diff = (render_config.water_level_offset - last_offset.offset).abs();
-    
-    if offset_diff > 0.01 && !water_query.is_empty() {
-        info!("🌊 Updating water level from {:.2} to {:.2}", 
-        last_offset.offset, render_config.water_level_offset);
-        
+    // The event covers the level controller; change detection covers the slider above
+    // when `WaterLevelPlugin` isn't there to turn its changes into events
+    let level = match level_changed.read().last() {
+        Some(changed) => {
+            debug!("🌊 Updating water level from {:.2} to {:.2}", changed.previous, changed.level);
+            changed.level
+        }
+        None if render_config.is_changed() => render_config.water_level_offset,
+        None => return,
+    };
 
-        for mut transform in water_query.iter_mut() {
-            log::info!("Water Y set to {}", transform.translation.y);
-            transform.translation.y = render_config.water_level_offset;
+    for mut transform in water_query.iter_mut() {
+        if (transform.translation.y - level).abs() > f32::EPSILON {
+            transform.translation.y = level;
         }
-        
-        last_offset.offset = render_config.water_level_offset;
     }
-}
\ No newline at end of file
+}
@@ -3,6 +3,7 @@ use bevy::{
 };
 use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
 
+use crate::game::EditorUiSet;
 use crate::heightmap_material::GpuHeightmapRenderConfig;
 
 /// GPU Heightmap material matching the WGSL struct
@@ -35,6 +36,18 @@ pub struct GpuHeightmapMaterial {
     #[uniform(100)]
     pub debug_options: Vec4,
 
+    // .x = caustic_intensity, .y = caustic_scale, .z = caustic_speed, .w = caustic_depth_fade
+    #[uniform(100)]
+    pub caustic_params: Vec4,
+
+    // .x = wave_amplitude, .y = wave_frequency, .z = wave_speed, .w = wave_steepness
+    #[uniform(100)]
+    pub water_params: Vec4,
+
+    // .x = water_surface_y, .y = unused, .z = unused, .w = time
+    #[uniform(100)]
+    pub water_misc: Vec4,
+
     #[texture(101)]
     #[sampler(102)]
     pub terrain_texture: Handle<Image>,
@@ -93,6 +106,9 @@ impl Default for GpuHeightmapMaterial {
             river_position: Vec4::new(0.0, -200.0, 1.0, 0.2),
             noise_config: Vec4::new(6.0, 2.5, 0.5, 0.0),
             debug_options: Vec4::new(0.0, 0.0, 0.0, 0.0),
+            caustic_params: Vec4::new(1.5, 3.0, 1.0, 0.3),
+            water_params: Vec4::new(3.0, 0.6, 0.8, 2.0),
+            water_misc: Vec4::new(0.5, 0.0, 0.0, 0.0),
             terrain_texture: Handle::default(),
         }
     }
@@ -151,7 +167,7 @@ impl Plugin for GpuHeightmapTerrainPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(MaterialPlugin::<CompleteGpuHeightmapMaterial>::default())
             .init_resource::<GpuHeightmapConfigUI>()
-            .add_systems(EguiPrimaryContextPass, gpu_heightmap_ui_system)
+            .add_systems(EguiPrimaryContextPass, gpu_heightmap_ui_system.in_set(EditorUiSet))
             .add_systems(Update, (
                 update_all_gpu_heightmap_materials,
             ));
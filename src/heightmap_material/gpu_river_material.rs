@@ -1,11 +1,17 @@
+use bevy::asset::LoadedFolder;
 use bevy::prelude::*;
 use bevy::pbr::{ExtendedMaterial, MaterialExtension};
 use bevy::render::render_resource::{AsBindGroup, ShaderRef};
 use bevy::reflect::Reflect;
 use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
 
+use crate::game::EditorUiSet;
 use crate::heightmap_material::gpu_heightmap_terrain::GpuHeightmapConfigUI;
-use crate::heightmap_material::GpuHeightmapRenderConfig;
+use crate::heightmap_material::water_presets::{
+    water_preset_buttons, ApplyWaterPreset, WaterPreset, WaterPresetLibrary, WaterPresetPlugin,
+    WaterPresetTarget, WaterPresetTargetPlugin, WaterPresetValues,
+};
+use crate::heightmap_material::{CompleteGpuHeightmapMaterial, GpuHeightmapRenderConfig};
 
 // Extended material for river‑masked water
 #[derive(Asset, AsBindGroup, Debug, Clone, Reflect)]
@@ -27,6 +33,12 @@ pub struct MaskedRiverWaterMaterial {
     pub terrain_params: Vec4,
     #[uniform(100)]
     pub debug_options: Vec4,
+    // .x origin_x .y origin_z .z world_size (0 = off) .w height_scale
+    #[uniform(100)]
+    pub ripple_params: Vec4,
+    // .x current height .y previous height, written by the ripple compute pass
+    #[texture(101, sample_type = "float", filterable = false)]
+    pub ripple_texture: Handle<Image>,
 }
 
 impl Default for MaskedRiverWaterMaterial {
@@ -38,6 +50,8 @@ impl Default for MaskedRiverWaterMaterial {
             river_position: Vec4::new(-256.0, 0.0, 1.0, 0.1),
             terrain_params: Vec4::new(0.005, 50.0, 8.0, 0.0),
             debug_options: Vec4::ZERO,
+            ripple_params: Vec4::ZERO,
+            ripple_texture: Handle::default(),
         }
     }
 }
@@ -73,7 +87,7 @@ pub struct MaskedRiverWaterConfig {
     pub reflectance: f32,
     pub roughness: f32,
     pub refraction_strength: f32,
-    // Caustic placeholders (not yet used in this shader – kept for parity)
+    // Caustics projected onto the terrain river bed
     pub caustic_intensity: f32,
     pub caustic_scale: f32,
     pub caustic_speed: f32,
@@ -103,32 +117,65 @@ impl Default for MaskedRiverWaterConfig {
     }
 }
 
-impl MaskedRiverWaterConfig {
-    pub fn apply_crystal_clear_preset(&mut self) {
-        self.water_clarity = 0.95;
-        self.reflectance = 0.9;
-        self.roughness = 0.02;
-        self.wave_amplitude = 0.1;
-        self.foam_intensity = 0.3;
+impl WaterPresetTarget for MaskedRiverWaterConfig {
+    fn preset_values(&self) -> WaterPresetValues {
+        WaterPresetValues {
+            wave_amplitude: Some(self.wave_amplitude),
+            wave_frequency: Some(self.wave_frequency),
+            wave_speed: Some(self.wave_speed),
+            wave_steepness: Some(self.wave_steepness),
+            foam_intensity: Some(self.foam_intensity),
+            foam_cutoff: Some(self.foam_cutoff),
+            water_clarity: Some(self.water_clarity),
+            transparency: None,
+            reflectance: Some(self.reflectance),
+            roughness: Some(self.roughness),
+            refraction_strength: Some(self.refraction_strength),
+            caustic_intensity: Some(self.caustic_intensity),
+            caustic_scale: Some(self.caustic_scale),
+            caustic_speed: Some(self.caustic_speed),
+            caustic_depth_fade: Some(self.caustic_depth_fade),
+        }
     }
-    pub fn apply_shallow_lagoon_preset(&mut self) {
-        self.water_clarity = 0.98;
-        self.reflectance = 0.85;
-        self.roughness = 0.01;
-        self.wave_amplitude = 0.05;
-        self.foam_intensity = 0.1;
+
+    fn apply_preset_values(&mut self, values: &WaterPresetValues) {
+        let set = |target: &mut f32, value: Option<f32>| {
+            if let Some(value) = value {
+                *target = value;
+            }
+        };
+        set(&mut self.wave_amplitude, values.wave_amplitude);
+        set(&mut self.wave_frequency, values.wave_frequency);
+        set(&mut self.wave_speed, values.wave_speed);
+        set(&mut self.wave_steepness, values.wave_steepness);
+        set(&mut self.foam_intensity, values.foam_intensity);
+        set(&mut self.foam_cutoff, values.foam_cutoff);
+        set(&mut self.water_clarity, values.water_clarity);
+        set(&mut self.reflectance, values.reflectance);
+        set(&mut self.roughness, values.roughness);
+        set(&mut self.refraction_strength, values.refraction_strength);
+        set(&mut self.caustic_intensity, values.caustic_intensity);
+        set(&mut self.caustic_scale, values.caustic_scale);
+        set(&mut self.caustic_speed, values.caustic_speed);
+        set(&mut self.caustic_depth_fade, values.caustic_depth_fade);
     }
 }
 
 pub struct MaskedRiverWaterPlugin;
 impl Plugin for MaskedRiverWaterPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<WaterPresetPlugin>() {
+            app.add_plugins(WaterPresetPlugin);
+        }
         app.init_resource::<MaskedRiverWaterConfig>()
             .add_plugins(MaterialPlugin::<CompleteMaskedRiverWaterMaterial>::default())
-            .add_systems(EguiPrimaryContextPass, masked_river_water_ui_system)
+            .add_plugins(WaterPresetTargetPlugin::<MaskedRiverWaterConfig>::default())
+            .add_systems(EguiPrimaryContextPass, masked_river_water_ui_system.in_set(EditorUiSet))
             .add_systems(Update, (
                 sync_masked_river_water_from_heightmap,
                 advance_masked_river_water_time,
+                sync_terrain_caustics_from_water,
+                advance_terrain_caustic_time,
             ));
     }
 }
@@ -136,6 +183,10 @@ impl Plugin for MaskedRiverWaterPlugin {
 fn masked_river_water_ui_system(
     mut contexts: EguiContexts,
     mut cfg: ResMut<MaskedRiverWaterConfig>,
+    mut library: Option<ResMut<WaterPresetLibrary>>,
+    folders: Res<Assets<LoadedFolder>>,
+    presets: Res<Assets<WaterPreset>>,
+    mut preset_requests: EventWriter<ApplyWaterPreset>,
 ) -> Result<(), BevyError> {
     let ctx = contexts.ctx_mut()?;
     egui::Window::new("Masked River Water Controls")
@@ -165,7 +216,7 @@ fn masked_river_water_ui_system(
             ui.add(egui::Slider::new(&mut cfg.refraction_strength, 0.0..=0.5).text("Refraction Strength"));
             ui.separator();
 
-            ui.heading("Caustic (Reserved)");
+            ui.heading("Caustics (River Bed)");
             ui.add(egui::Slider::new(&mut cfg.caustic_intensity, 0.0..=3.0).text("Intensity"));
             ui.add(egui::Slider::new(&mut cfg.caustic_scale, 1.0..=10.0).text("Scale"));
             ui.add(egui::Slider::new(&mut cfg.caustic_speed, 0.0..=3.0).text("Speed"));
@@ -173,54 +224,10 @@ fn masked_river_water_ui_system(
             ui.separator();
 
             ui.heading("Presets");
-            ui.horizontal(|ui| {
-                if ui.button("Calm Lake").clicked() {
-                    cfg.wave_amplitude = 0.05;
-                    cfg.wave_frequency = 0.2;
-                    cfg.wave_speed = 0.3;
-                    cfg.wave_steepness = 2.0;
-                    cfg.foam_intensity = 0.5;
-                    cfg.foam_cutoff = 0.8;
-                    cfg.water_clarity = 0.8;
-                }
-                if ui.button("Ocean Waves").clicked() {
-                    cfg.wave_amplitude = 0.3;
-                    cfg.wave_frequency = 0.15;
-                    cfg.wave_speed = 0.6;
-                    cfg.wave_steepness = 4.0;
-                    cfg.foam_intensity = 1.5;
-                    cfg.foam_cutoff = 0.6;
-                    cfg.water_clarity = 0.5;
-                }
-            });
-            ui.horizontal(|ui| {
-                if ui.button("Fast Stream").clicked() {
-                    cfg.wave_amplitude = 0.08;
-                    cfg.wave_frequency = 0.8;
-                    cfg.wave_speed = 2.0;
-                    cfg.wave_steepness = 2.0;
-                    cfg.foam_intensity = 0.8;
-                    cfg.foam_cutoff = 0.7;
-                    cfg.water_clarity = 0.7;
-                }
-                if ui.button("Rough Sea").clicked() {
-                    cfg.wave_amplitude = 0.4;
-                    cfg.wave_frequency = 0.12;
-                    cfg.wave_speed = 0.8;
-                    cfg.wave_steepness = 5.0;
-                    cfg.foam_intensity = 2.0;
-                    cfg.foam_cutoff = 0.5;
-                    cfg.water_clarity = 0.4;
-                }
-            });
-            ui.horizontal(|ui| {
-                if ui.button("Crystal Clear").clicked() {
-                    cfg.apply_crystal_clear_preset();
-                }
-                if ui.button("Shallow Lagoon").clicked() {
-                    cfg.apply_shallow_lagoon_preset();
-                }
-            });
+            if let Some(library) = library.as_deref_mut() {
+                water_preset_buttons(ui, library, &folders, &presets, &mut preset_requests);
+            }
+            ui.separator();
 
             ui.collapsing("Debug Values", |ui| {
                 ui.label(format!(
@@ -286,6 +293,9 @@ fn sync_masked_river_water_from_heightmap(
         mat.base.perceptual_roughness = water_cfg.roughness;
         mat.base.reflectance = water_cfg.reflectance;
         mat.base.base_color = Color::srgba(0.0, 0.4, 0.8, water_cfg.water_clarity);
+        // The surface is also seen from below when the camera dives
+        mat.base.cull_mode = None;
+        mat.base.double_sided = true;
 
         if let Some(h) = height_cfg.as_ref() {
             mat.extension.river_params = Vec4::new(
@@ -327,4 +337,46 @@ fn advance_masked_river_water_time(
         m.w += dt;
         mat.extension.misc_params = m;
     }
-}
\ No newline at end of file
+}
+
+fn sync_terrain_caustics_from_water(
+    water_cfg: Res<MaskedRiverWaterConfig>,
+    render_cfg: Option<Res<GpuHeightmapRenderConfig>>,
+    mut materials: ResMut<Assets<CompleteGpuHeightmapMaterial>>,
+) {
+    if !water_cfg.is_changed() && render_cfg.as_ref().map_or(true, |r| !r.is_changed()) {
+        return;
+    }
+
+    let water_level = render_cfg
+        .as_ref()
+        .map(|rc| rc.water_level_offset)
+        .unwrap_or(0.0);
+
+    for (_, mat) in materials.iter_mut() {
+        mat.extension.caustic_params = Vec4::new(
+            water_cfg.caustic_intensity,
+            water_cfg.caustic_scale,
+            water_cfg.caustic_speed,
+            water_cfg.caustic_depth_fade,
+        );
+        // Keep the simulated surface in sync with the visible water
+        mat.extension.water_params = Vec4::new(
+            water_cfg.wave_amplitude,
+            water_cfg.wave_frequency,
+            water_cfg.wave_speed,
+            water_cfg.wave_steepness,
+        );
+        mat.extension.water_misc.x = water_level;
+    }
+}
+
+fn advance_terrain_caustic_time(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<CompleteGpuHeightmapMaterial>>,
+) {
+    let dt = time.delta_secs();
+    for (_, mat) in materials.iter_mut() {
+        mat.extension.water_misc.w += dt;
+    }
+}
@@ -1,7 +1,17 @@
 pub mod gpu_heightmap_renderer;
 pub mod gpu_heightmap_terrain;
 pub mod gpu_river_material;
+pub mod terrain_sampler;
+pub mod underwater;
+pub mod water_level;
+pub mod water_presets;
+pub mod water_ripples;
+pub mod water_surface;
 
 pub use gpu_heightmap_renderer::*;
 pub use gpu_heightmap_terrain::*;
 pub use gpu_river_material::*;
+pub use terrain_sampler::*;
+pub use underwater::*;
+pub use water_level::*;
+pub use water_ripples::*;
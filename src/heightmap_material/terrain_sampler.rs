@@ -0,0 +1,318 @@
+use bevy::prelude::*;
+
+use crate::heightmap_material::GpuHeightmapConfigUI;
+
+/// CPU port of `generate_height` from `heightmap_terrain_2.wgsl`.
+/// Must stay in step with the shader so gameplay agrees with what is drawn.
+#[derive(Clone, Debug)]
+pub struct TerrainSampler {
+    terrain_scale: f32,
+    terrain_amplitude: f32,
+    river_depth: f32,
+    river_width: f32,
+    bank_slope_distance: f32,
+    meander_frequency: f32,
+    meander_amplitude: f32,
+    erosion_strength: f32,
+    erosion_radius: f32,
+    valley_flattening: f32,
+    erosion_smoothing: f32,
+    flat_area_radius: f32,
+    flat_area_strength: f32,
+    hill_steepness: f32,
+    terrain_roughness: f32,
+    river_start: Vec2,
+    river_dir: Vec2,
+    noise_octaves: i32,
+    noise_lacunarity: f32,
+    noise_persistence: f32,
+}
+
+impl TerrainSampler {
+    pub fn new(config: &GpuHeightmapConfigUI) -> Self {
+        let dir = Vec2::new(config.river_dir_x, config.river_dir_y);
+        Self {
+            terrain_scale: config.terrain_scale,
+            terrain_amplitude: config.terrain_amplitude,
+            river_depth: config.river_depth,
+            river_width: config.river_width,
+            bank_slope_distance: config.bank_slope_distance,
+            meander_frequency: config.meander_frequency,
+            meander_amplitude: config.meander_amplitude,
+            erosion_strength: config.erosion_strength,
+            erosion_radius: config.erosion_radius,
+            valley_flattening: config.valley_flattening,
+            erosion_smoothing: config.erosion_smoothing,
+            flat_area_radius: config.flat_area_radius,
+            flat_area_strength: config.flat_area_strength,
+            hill_steepness: config.hill_steepness,
+            terrain_roughness: config.terrain_roughness,
+            river_start: Vec2::new(config.river_start_x, config.river_start_y),
+            // The shader leaves a zero vector alone
+            river_dir: if dir.length() > 0.0 { dir.normalize() } else { dir },
+            noise_octaves: config.noise_octaves,
+            noise_lacunarity: config.noise_lacunarity,
+            noise_persistence: config.noise_persistence,
+        }
+    }
+
+    /// Terrain height at world XZ `pos`
+    pub fn height(&self, pos: Vec2) -> f32 {
+        let base_terrain = self.enhanced_terrain_height(pos);
+        let (river_modification, erosion_factor) = self.river_effects(pos);
+        self.apply_erosion(base_terrain, pos, erosion_factor) + river_modification
+    }
+
+    /// Unit direction the river flows along before meandering
+    pub fn river_axis(&self) -> Vec2 {
+        self.river_dir
+    }
+
+    pub fn river_perpendicular(&self) -> Vec2 {
+        Vec2::new(-self.river_dir.y, self.river_dir.x)
+    }
+
+    /// Distance along the river axis for world XZ `pos`
+    pub fn distance_along(&self, pos: Vec2) -> f32 {
+        (pos - self.river_start).dot(self.river_dir)
+    }
+
+    /// Point on the river axis `along` units downstream, offset `lateral` units sideways
+    pub fn axis_point(&self, along: f32, lateral: f32) -> Vec2 {
+        self.river_start + self.river_dir * along + self.river_perpendicular() * lateral
+    }
+
+    /// Meandering centre line of the river
+    pub fn river_center(&self, along: f32) -> Vec2 {
+        self.axis_point(along, self.meander(along))
+    }
+
+    /// Carved width of the water channel at `along`
+    pub fn river_width_at(&self, along: f32) -> f32 {
+        let width_noise = sample_noise(Vec2::new(along * 0.0005, 0.0));
+        self.river_width * (1.0 + width_noise * 0.3)
+    }
+
+    pub fn meander_amplitude(&self) -> f32 {
+        self.meander_amplitude
+    }
+
+    fn river_effects(&self, pos: Vec2) -> (f32, f32) {
+        let along = self.distance_along(pos);
+        let distance_to_river = pos.distance(self.river_center(along));
+        let width = self.river_width_at(along);
+        (
+            self.river_profile(distance_to_river, width),
+            self.erosion_factor(distance_to_river, width),
+        )
+    }
+
+    /// Sideways offset of the river centre from its axis at `along`
+    pub fn meander(&self, along: f32) -> f32 {
+        let tau = std::f32::consts::TAU;
+        let meander_phase = along * self.meander_frequency;
+
+        let primary = (meander_phase * tau).sin();
+        let secondary = (along * self.meander_frequency * 1.7 * tau).sin() * 0.4;
+        let chaos = sample_fbm(
+            Vec2::new(along * 0.001, 0.0),
+            self.noise_octaves,
+            self.noise_lacunarity,
+            self.noise_persistence,
+        );
+        let scale_factor = 1.0 + sample_noise(Vec2::new(along * 0.0003, 0.0)) * 0.4;
+        let asymmetry = sample_noise(Vec2::new(meander_phase * 0.8, 1000.0));
+
+        let base = primary * 0.7 + secondary * 0.3;
+        let total = (base + chaos * 0.6 * 0.5 + asymmetry * 0.2) * scale_factor;
+        total * self.meander_amplitude
+    }
+
+    fn river_profile(&self, distance_to_river: f32, river_width: f32) -> f32 {
+        let water_edge = river_width * 0.5;
+        let bank_end = water_edge + self.bank_slope_distance;
+
+        if distance_to_river <= water_edge {
+            -self.river_depth
+        } else if distance_to_river <= bank_end {
+            let progress = (distance_to_river - water_edge) / self.bank_slope_distance;
+            let smooth1 = 1.0 - progress.powf(3.0);
+            let smooth2 = ((1.0 - progress) * std::f32::consts::FRAC_PI_2).sin();
+            let smooth3 = (1.0 + (progress * std::f32::consts::PI).cos()) * 0.5;
+            -self.river_depth * (smooth1 * 0.5 + smooth2 * 0.3 + smooth3 * 0.2)
+        } else {
+            0.0
+        }
+    }
+
+    fn erosion_factor(&self, distance_to_river: f32, river_width: f32) -> f32 {
+        let water_edge = river_width * 0.5;
+        let erosion_end = water_edge + self.erosion_radius;
+
+        if distance_to_river <= water_edge {
+            self.erosion_strength
+        } else if distance_to_river <= erosion_end {
+            let progress = (distance_to_river - water_edge) / self.erosion_radius;
+            self.erosion_strength * (1.0 - progress).powf(2.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn apply_erosion(&self, base_height: f32, pos: Vec2, erosion_factor: f32) -> f32 {
+        if erosion_factor <= 0.0 {
+            return base_height;
+        }
+        let valley = self.valley_floor_height(pos);
+        let flatten = self.valley_flattening * erosion_factor;
+        let flattened = base_height * (1.0 - flatten) + valley * flatten;
+        self.apply_smoothing(flattened, pos, erosion_factor)
+    }
+
+    fn apply_smoothing(&self, height: f32, pos: Vec2, erosion_factor: f32) -> f32 {
+        let strength = self.erosion_smoothing * erosion_factor;
+        if strength <= 0.0 {
+            return height;
+        }
+
+        let sample_radius = 2.0;
+        let mut sum = height;
+        let mut count = 1.0;
+        for i in 0..4 {
+            let angle = (i as f32 / 4.0) * std::f32::consts::TAU;
+            let sample_pos = pos + Vec2::new(angle.cos(), angle.sin()) * sample_radius;
+            sum += self.simple_terrain_height(sample_pos);
+            count += 1.0;
+        }
+
+        height * (1.0 - strength) + (sum / count) * strength
+    }
+
+    fn enhanced_terrain_height(&self, pos: Vec2) -> f32 {
+        let mut base = sample_fbm_rotated(pos * self.terrain_scale, 6, 2.0, 0.5);
+        base = base.abs().powf(self.hill_steepness) * sign(base);
+
+        let hill_detail = sample_fbm_rotated(pos * self.terrain_scale * 2.0, 6, 2.2, 0.6)
+            * 0.3
+            * self.terrain_roughness;
+        let detail = sample_fbm_rotated(pos * 0.05, 4, 2.0, 0.5) * 0.1 * self.terrain_roughness;
+
+        let flat_mask = self.flat_area_mask(pos);
+        let enhanced = (base + hill_detail + detail) * self.terrain_amplitude;
+        enhanced * (1.0 - flat_mask) + (enhanced * 0.3) * flat_mask
+    }
+
+    fn simple_terrain_height(&self, pos: Vec2) -> f32 {
+        let base = sample_fbm_rotated(pos * self.terrain_scale, 6, 2.0, 0.5);
+        let detail = sample_noise(pos * 0.05) * 0.1;
+        (base + detail) * self.terrain_amplitude
+    }
+
+    fn valley_floor_height(&self, pos: Vec2) -> f32 {
+        let valley_base = sample_fbm_rotated(pos * self.terrain_scale * 0.3, 5, 2.0, 0.5);
+        let river_slope = self.distance_along(pos) * 0.001;
+        valley_base * self.terrain_amplitude * 0.3 + river_slope
+    }
+
+    fn flat_area_mask(&self, pos: Vec2) -> f32 {
+        let center_value = sample_noise(pos * 0.002);
+        if center_value <= 0.6 {
+            return 0.0;
+        }
+
+        let sample_count = 8;
+        let mut total = 0.0;
+        for i in 0..sample_count {
+            let angle = (i as f32 / sample_count as f32) * std::f32::consts::TAU;
+            let sample_pos = pos + Vec2::new(angle.cos(), angle.sin()) * self.flat_area_radius * 0.5;
+            total += sample_noise(sample_pos * 0.002);
+        }
+        let average = total / sample_count as f32;
+
+        let distance_factor = 1.0 - (center_value - 0.6) / 0.4;
+        (average * distance_factor * self.flat_area_strength).clamp(0.0, 1.0)
+    }
+}
+
+/* ------------------------------- Noise -------------------------------- */
+
+// WGSL semantics: fract is x - floor(x) and sign(0) is 0
+
+fn fract2(v: Vec2) -> Vec2 {
+    v - v.floor()
+}
+
+fn fract3(v: Vec3) -> Vec3 {
+    v - v.floor()
+}
+
+fn sign(x: f32) -> f32 {
+    if x > 0.0 {
+        1.0
+    } else if x < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+// mat2x2<f32>(c, -s, s, c) * v, with WGSL's column-major constructor
+fn rotate2d(angle: f32, v: Vec2) -> Vec2 {
+    let (s, c) = angle.sin_cos();
+    Vec2::new(c * v.x + s * v.y, -s * v.x + c * v.y)
+}
+
+fn hash22(p: Vec2) -> Vec2 {
+    let mut p3 = fract3(Vec3::new(p.x, p.y, p.x) * Vec3::new(0.1031, 0.1030, 0.0973));
+    p3 += Vec3::splat(p3.dot(Vec3::new(p3.y, p3.z, p3.x) + Vec3::splat(33.33)));
+    fract2((Vec2::new(p3.x, p3.x) + Vec2::new(p3.y, p3.z)) * Vec2::new(p3.z, p3.y))
+}
+
+fn gradient_noise(p: Vec2) -> f32 {
+    let i = p.floor();
+    let f = fract2(p);
+
+    let a = hash22(i);
+    let b = hash22(i + Vec2::new(1.0, 0.0));
+    let c = hash22(i + Vec2::new(0.0, 1.0));
+    let d = hash22(i + Vec2::new(1.0, 1.0));
+
+    let u = f * f * (Vec2::splat(3.0) - 2.0 * f);
+
+    let ab = a.dot(f).lerp(b.dot(f - Vec2::new(1.0, 0.0)), u.x);
+    let cd = c.dot(f - Vec2::new(0.0, 1.0)).lerp(d.dot(f - Vec2::new(1.0, 1.0)), u.x);
+    ab.lerp(cd, u.y)
+}
+
+fn sample_noise(coord: Vec2) -> f32 {
+    gradient_noise(coord) * 0.5 + 0.5
+}
+
+fn sample_fbm(coord: Vec2, octaves: i32, lacunarity: f32, persistence: f32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    for _ in 0..octaves {
+        value += amplitude * sample_noise(coord * frequency);
+        amplitude *= persistence;
+        frequency *= lacunarity;
+    }
+    value
+}
+
+fn sample_fbm_rotated(coord: Vec2, octaves: i32, lacunarity: f32, persistence: f32) -> f32 {
+    const BASE_ROTATION: f32 = 0.52359877559;
+
+    let mut value = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    let mut max_value = 0.0;
+    for i in 0..octaves {
+        let rotated = rotate2d(BASE_ROTATION * i as f32, coord * frequency);
+        value += amplitude * (sample_noise(rotated) - 0.5);
+        max_value += amplitude;
+        amplitude *= persistence;
+        frequency *= lacunarity;
+    }
+    value / max_value
+}
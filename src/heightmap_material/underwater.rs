@@ -0,0 +1,464 @@
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::ecs::query::QueryItem;
+use bevy::pbr::{DistanceFog, FogFalloff};
+use bevy::prelude::*;
+use bevy::render::extract_component::{ExtractComponent, ExtractComponentPlugin};
+use bevy::render::render_graph::{NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner};
+use bevy::render::render_resource::binding_types::{sampler, texture_2d, uniform_buffer_sized};
+use bevy::render::render_resource::{
+    BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, BufferUsages, CachedRenderPipelineId,
+    ColorTargetState, ColorWrites, FragmentState, MultisampleState, Operations, PipelineCache,
+    PrimitiveState, RawBufferVec, RenderPassColorAttachment, RenderPassDescriptor,
+    RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor, ShaderStages,
+    SpecializedRenderPipeline, SpecializedRenderPipelines, TextureFormat, TextureSampleType,
+};
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::view::ViewTarget;
+use bevy::render::{Render, RenderApp, RenderSet};
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+
+use crate::game::EditorUiSet;
+use crate::heightmap_material::water_surface::WaterSurfaceSampler;
+use crate::heightmap_material::{
+    CompleteMaskedRiverWaterMaterial, GpuHeightmapConfigUI, GpuHeightmapRenderConfig, GpuHeightmapWater,
+    MaskedRiverWaterConfig,
+};
+
+const UNDERWATER_SHADER_PATH: &str = "shaders/underwater_post.wgsl";
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Added to every 3D camera; drives the underwater post-process.
+/// `submerged` blends from 0 (above water) to 1 (fully below) across the waterline.
+#[derive(Component, Clone, Debug, Default, ExtractComponent)]
+pub struct UnderwaterCamera {
+    pub submerged: f32,
+    /// Distance below the water surface, 0 when above
+    pub depth: f32,
+    pub time: f32,
+    /// How much the camera looks towards the surface, -1..1
+    pub look_up: f32,
+    pub tint: Vec4,
+    pub absorption: Vec3,
+    pub distortion: f32,
+    pub light_shafts: f32,
+}
+
+/// Fog the camera had before it went under, restored when it surfaces
+#[derive(Component)]
+struct AboveWaterFog(Option<DistanceFog>);
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource)]
+pub struct UnderwaterConfig {
+    pub enabled: bool,
+    /// Fog colour seen through the water
+    pub fog_color: Color,
+    /// Distance at which objects fade completely into the fog
+    pub visibility: f32,
+    /// Colour the image is pushed towards, alpha = strength
+    pub tint: Color,
+    /// Per-channel light absorption per world unit of camera depth (red goes first)
+    pub absorption: Vec3,
+    pub distortion: f32,
+    pub light_shafts: f32,
+    /// Height of the soft transition band around the waterline
+    pub waterline_blend: f32,
+}
+
+impl Default for UnderwaterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fog_color: Color::srgb(0.05, 0.22, 0.3),
+            visibility: 60.0,
+            tint: Color::srgba(0.1, 0.35, 0.45, 0.35),
+            absorption: Vec3::new(0.08, 0.03, 0.015),
+            distortion: 1.0,
+            light_shafts: 0.6,
+            waterline_blend: 0.3,
+        }
+    }
+}
+
+/// Whether the active camera is below the river surface
+#[derive(Resource, Default, Debug)]
+pub struct UnderwaterState {
+    pub is_underwater: bool,
+    pub depth: f32,
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct UnderwaterLabel;
+
+pub struct UnderwaterPlugin;
+
+impl Plugin for UnderwaterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UnderwaterConfig>()
+            .init_resource::<UnderwaterState>()
+            .add_plugins(ExtractComponentPlugin::<UnderwaterCamera>::default())
+            .add_systems(EguiPrimaryContextPass, underwater_ui_system.in_set(EditorUiSet))
+            .add_systems(Update, (
+                attach_underwater_camera,
+                update_underwater_camera,
+                apply_underwater_fog,
+            ).chain());
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .init_resource::<SpecializedRenderPipelines<UnderwaterPipeline>>()
+            .add_systems(Render, (
+                prepare_underwater_uniforms.in_set(RenderSet::PrepareResources),
+                prepare_underwater_pipelines.in_set(RenderSet::Prepare),
+            ))
+            .add_render_graph_node::<ViewNodeRunner<UnderwaterNode>>(Core3d, UnderwaterLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (Node3d::Tonemapping, UnderwaterLabel, Node3d::EndMainPassPostProcessing),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app.init_resource::<UnderwaterPipeline>();
+    }
+}
+
+/* ---------------------------- Main World ------------------------------ */
+
+fn attach_underwater_camera(
+    mut commands: Commands,
+    cameras: Query<Entity, (With<Camera3d>, Without<UnderwaterCamera>)>,
+) {
+    for entity in &cameras {
+        commands.entity(entity).insert(UnderwaterCamera::default());
+    }
+}
+
+fn update_underwater_camera(
+    config: Res<UnderwaterConfig>,
+    water_cfg: Res<MaskedRiverWaterConfig>,
+    height_cfg: Res<GpuHeightmapConfigUI>,
+    render_cfg: Res<GpuHeightmapRenderConfig>,
+    materials: Res<Assets<CompleteMaskedRiverWaterMaterial>>,
+    water_query: Query<(&GlobalTransform, &MeshMaterial3d<CompleteMaskedRiverWaterMaterial>), With<GpuHeightmapWater>>,
+    mut cameras: Query<(&GlobalTransform, &mut UnderwaterCamera)>,
+    mut state: ResMut<UnderwaterState>,
+) {
+    // The water plane's own clock and height, so the test matches what is drawn
+    let water = water_query
+        .iter()
+        .next()
+        .filter(|_| render_cfg.enable_water_rendering)
+        .map(|(transform, material)| {
+            let time = materials
+                .get(&material.0)
+                .map_or(0.0, |m| m.extension.misc_params.w);
+            (transform.translation().y, time)
+        });
+
+    let mut sampler = WaterSurfaceSampler::new(&render_cfg, &water_cfg, &height_cfg);
+    // The pass runs after tonemapping, so the tint stays in display (sRGB) space
+    let tint = config.tint.to_srgba();
+
+    state.is_underwater = false;
+    state.depth = 0.0;
+
+    for (transform, mut underwater) in &mut cameras {
+        let pos = transform.translation();
+        let mut depth = 0.0;
+        let mut submerged = 0.0;
+        let mut time = 0.0;
+
+        if let Some((level, water_time)) = water.filter(|_| config.enabled) {
+            sampler.water_level = level;
+            if sampler.is_over_water(pos.xz()) {
+                let surface = sampler.surface_height(pos.xz(), water_time);
+                let blend = config.waterline_blend.max(0.001);
+                depth = (surface - pos.y).max(0.0);
+                submerged = ((surface - pos.y + blend) / (2.0 * blend)).clamp(0.0, 1.0);
+                time = water_time;
+            }
+        }
+
+        underwater.submerged = submerged;
+        underwater.depth = depth;
+        underwater.time = time;
+        underwater.look_up = transform.forward().y;
+        underwater.tint = Vec4::new(tint.red, tint.green, tint.blue, tint.alpha);
+        underwater.absorption = config.absorption;
+        underwater.distortion = config.distortion;
+        underwater.light_shafts = config.light_shafts;
+
+        if submerged > 0.5 {
+            state.is_underwater = true;
+            state.depth = state.depth.max(depth);
+        }
+    }
+}
+
+/// Swaps in underwater fog when the camera goes under and puts the old fog back when it
+/// surfaces. In between, the fog is updated in place rather than reinserted.
+fn apply_underwater_fog(
+    mut commands: Commands,
+    config: Res<UnderwaterConfig>,
+    mut cameras: Query<(Entity, &UnderwaterCamera, Option<&mut DistanceFog>, Option<&AboveWaterFog>)>,
+) {
+    for (entity, underwater, fog, saved) in &mut cameras {
+        if underwater.submerged <= 0.5 {
+            if let Some(saved) = saved {
+                let mut entity_commands = commands.entity(entity);
+                entity_commands.remove::<AboveWaterFog>();
+                match &saved.0 {
+                    Some(previous) => {
+                        entity_commands.insert(previous.clone());
+                    }
+                    None => {
+                        entity_commands.remove::<DistanceFog>();
+                    }
+                }
+            }
+            continue;
+        }
+
+        // Murkier the deeper the camera goes
+        let absorbed = (-config.absorption * underwater.depth).exp();
+        let extinction = Color::srgb(absorbed.x, absorbed.y, absorbed.z);
+        let underwater_fog = DistanceFog {
+            color: config.fog_color,
+            directional_light_color: Color::NONE,
+            directional_light_exponent: 8.0,
+            falloff: FogFalloff::from_visibility_colors(
+                config.visibility.max(1.0),
+                extinction,
+                config.fog_color,
+            ),
+        };
+        match (fog, saved) {
+            (Some(mut fog), Some(_)) => *fog = underwater_fog,
+            (None, Some(_)) => {
+                commands.entity(entity).insert(underwater_fog);
+            }
+            (fog, None) => {
+                let previous = fog.map(|fog| fog.clone());
+                commands.entity(entity).insert((AboveWaterFog(previous), underwater_fog));
+            }
+        }
+    }
+}
+
+fn underwater_ui_system(
+    mut contexts: EguiContexts,
+    mut config: ResMut<UnderwaterConfig>,
+    state: Res<UnderwaterState>,
+) -> Result<(), BevyError> {
+    let ctx = contexts.ctx_mut()?;
+    egui::Window::new("Underwater")
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            ui.checkbox(&mut config.enabled, "Enable Underwater Mode");
+            ui.label(if state.is_underwater {
+                format!("Camera submerged, depth {:.1}", state.depth)
+            } else {
+                "Camera above water".to_string()
+            });
+            ui.separator();
+
+            ui.heading("Fog");
+            let mut fog = config.fog_color.to_srgba().to_f32_array_no_alpha();
+            if ui.color_edit_button_rgb(&mut fog).changed() {
+                config.fog_color = Color::srgb(fog[0], fog[1], fog[2]);
+            }
+            ui.add(egui::Slider::new(&mut config.visibility, 5.0..=300.0).text("Visibility"));
+            ui.separator();
+
+            ui.heading("Tint / Absorption");
+            let mut tint = config.tint.to_srgba().to_f32_array();
+            if ui.color_edit_button_rgba_unmultiplied(&mut tint).changed() {
+                config.tint = Color::srgba(tint[0], tint[1], tint[2], tint[3]);
+            }
+            ui.add(egui::Slider::new(&mut config.absorption.x, 0.0..=0.3).text("Red Absorption"));
+            ui.add(egui::Slider::new(&mut config.absorption.y, 0.0..=0.3).text("Green Absorption"));
+            ui.add(egui::Slider::new(&mut config.absorption.z, 0.0..=0.3).text("Blue Absorption"));
+            ui.separator();
+
+            ui.heading("Effects");
+            ui.add(egui::Slider::new(&mut config.distortion, 0.0..=3.0).text("Distortion"));
+            ui.add(egui::Slider::new(&mut config.light_shafts, 0.0..=2.0).text("Light Shafts"));
+            ui.add(egui::Slider::new(&mut config.waterline_blend, 0.01..=2.0).text("Waterline Blend"));
+        });
+
+    Ok(())
+}
+
+/* ---------------------------- Render World ---------------------------- */
+
+/// Packed as vec4s to match `UnderwaterSettings` in the shader:
+/// [0] = tint (rgb, strength), [1] = absorption (rgb, depth),
+/// [2] = (distortion, light shafts, time, look up), [3] = (submerged, unused...)
+#[derive(Component)]
+struct UnderwaterUniform(RawBufferVec<Vec4>);
+
+fn prepare_underwater_uniforms(
+    mut commands: Commands,
+    mut views: Query<(Entity, &UnderwaterCamera, Option<&mut UnderwaterUniform>)>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    for (entity, settings, uniform) in &mut views {
+        let mut created = None;
+        let buffer = match uniform {
+            Some(uniform) => &mut uniform.into_inner().0,
+            None => created.insert(RawBufferVec::new(BufferUsages::UNIFORM)),
+        };
+
+        buffer.clear();
+        buffer.push(settings.tint);
+        buffer.push(settings.absorption.extend(settings.depth));
+        buffer.push(Vec4::new(settings.distortion, settings.light_shafts, settings.time, settings.look_up));
+        buffer.push(Vec4::new(settings.submerged, 0.0, 0.0, 0.0));
+        buffer.write_buffer(&render_device, &render_queue);
+
+        if let Some(buffer) = created {
+            commands.entity(entity).insert(UnderwaterUniform(buffer));
+        }
+    }
+}
+
+#[derive(Resource)]
+struct UnderwaterPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+/// Pipeline specialised for the format of this view's target, e.g. `Rgba16Float` when HDR
+#[derive(Component)]
+struct UnderwaterPipelineId(CachedRenderPipelineId);
+
+impl FromWorld for UnderwaterPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "underwater_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer_sized(false, None),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.load_asset(UNDERWATER_SHADER_PATH);
+
+        Self {
+            layout,
+            sampler,
+            shader,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for UnderwaterPipeline {
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("underwater_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+fn prepare_underwater_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<UnderwaterPipeline>>,
+    pipeline: Res<UnderwaterPipeline>,
+    views: Query<(Entity, &ViewTarget), With<UnderwaterCamera>>,
+) {
+    for (entity, view_target) in &views {
+        let id = pipelines.specialize(&pipeline_cache, &pipeline, view_target.main_texture_format());
+        commands.entity(entity).insert(UnderwaterPipelineId(id));
+    }
+}
+
+#[derive(Default)]
+struct UnderwaterNode;
+
+impl ViewNode for UnderwaterNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static UnderwaterCamera,
+        &'static UnderwaterUniform,
+        &'static UnderwaterPipelineId,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, settings, uniform, pipeline_id): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        if settings.submerged <= 0.0 {
+            return Ok(());
+        }
+
+        let pipeline = world.resource::<UnderwaterPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(render_pipeline) = pipeline_cache.get_render_pipeline(pipeline_id.0) else {
+            return Ok(());
+        };
+        let Some(settings_binding) = uniform.0.binding() else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "underwater_bind_group",
+            &pipeline.layout,
+            &BindGroupEntries::sequential((post_process.source, &pipeline.sampler, settings_binding)),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("underwater_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(render_pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+
+use crate::game::EditorUiSet;
+use crate::heightmap_material::gpu_heightmap_renderer::{update_water_level_on_change, GpuHeightmapRenderConfig};
+
+/* ------------------------------- Events ------------------------------- */
+
+/// Sent whenever `water_level_offset` moves, whether the controller, a script or the UI changed it.
+/// Anything that depends on the waterline (masks, buoyancy, shoreline) should listen for this.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct WaterLevelChanged {
+    pub previous: f32,
+    pub level: f32,
+}
+
+/// Scripted water level changes, e.g. a destroyed dam flooding the valley
+#[derive(Event, Clone, Debug)]
+pub enum WaterLevelCommand {
+    /// Ease to `level` over `duration` seconds and hold it
+    TransitionTo { level: f32, duration: f32, ease: EaseFunction },
+    /// Oscillate around `base`
+    StartTide { base: f32, amplitude: f32, period: f32 },
+    PlayKeyframes { keys: Vec<WaterLevelKeyframe>, looping: bool },
+    /// Hand the water level back to the UI
+    Stop,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Clone, Copy, Debug)]
+pub struct WaterLevelKeyframe {
+    /// Seconds since the keyframes started
+    pub time: f32,
+    pub level: f32,
+    /// Easing used to arrive at this keyframe from the previous one
+    pub ease: EaseFunction,
+}
+
+impl WaterLevelKeyframe {
+    pub fn new(time: f32, level: f32, ease: EaseFunction) -> Self {
+        Self { time, level, ease }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum WaterLevelMode {
+    /// Not driven; `water_level_offset` is whatever the UI set
+    #[default]
+    Manual,
+    Hold(f32),
+    Tide { base: f32, amplitude: f32, period: f32 },
+    Keyframes { keys: Vec<WaterLevelKeyframe>, looping: bool },
+}
+
+impl WaterLevelMode {
+    /// Level at `elapsed` seconds into this mode, `None` in manual mode
+    pub fn sample(&self, elapsed: f32) -> Option<f32> {
+        match self {
+            WaterLevelMode::Manual => None,
+            WaterLevelMode::Hold(level) => Some(*level),
+            WaterLevelMode::Tide { base, amplitude, period } => {
+                let phase = elapsed / period.max(0.001) * std::f32::consts::TAU;
+                Some(base + amplitude * phase.sin())
+            }
+            WaterLevelMode::Keyframes { keys, looping } => sample_keyframes(keys, elapsed, *looping),
+        }
+    }
+
+    /// Level the mode rests at, without the tide or keyframe motion; `None` in manual mode.
+    /// Use this for anything that must not depend on when it was sampled.
+    pub fn base_level(&self) -> Option<f32> {
+        match self {
+            WaterLevelMode::Manual => None,
+            WaterLevelMode::Hold(level) => Some(*level),
+            WaterLevelMode::Tide { base, .. } => Some(*base),
+            WaterLevelMode::Keyframes { keys, .. } => keys.first().map(|key| key.level),
+        }
+    }
+}
+
+fn sample_keyframes(keys: &[WaterLevelKeyframe], elapsed: f32, looping: bool) -> Option<f32> {
+    let first = keys.first()?;
+    let last = keys.last()?;
+
+    let t = if looping && last.time > 0.0 {
+        elapsed.rem_euclid(last.time)
+    } else {
+        elapsed
+    };
+    if t <= first.time {
+        return Some(first.level);
+    }
+
+    for pair in keys.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if t <= to.time {
+            let span = (to.time - from.time).max(0.001);
+            let eased = to.ease.sample_clamped((t - from.time) / span);
+            return Some(from.level.lerp(to.level, eased));
+        }
+    }
+    Some(last.level)
+}
+
+#[derive(Clone, Debug)]
+struct WaterLevelBlend {
+    from: f32,
+    elapsed: f32,
+    duration: f32,
+    ease: EaseFunction,
+}
+
+/// Animates `GpuHeightmapRenderConfig::water_level_offset`.
+/// Switching modes eases from the current level instead of snapping.
+#[derive(Resource)]
+pub struct WaterLevelController {
+    pub mode: WaterLevelMode,
+    /// Seconds spent in the current mode
+    pub elapsed: f32,
+    pub paused: bool,
+    /// Blend time used when a tide or keyframes take over
+    pub mode_blend_duration: f32,
+    blend: Option<WaterLevelBlend>,
+    last_level: Option<f32>,
+}
+
+impl Default for WaterLevelController {
+    fn default() -> Self {
+        Self {
+            mode: WaterLevelMode::Manual,
+            elapsed: 0.0,
+            paused: false,
+            mode_blend_duration: 2.0,
+            blend: None,
+            last_level: None,
+        }
+    }
+}
+
+impl WaterLevelController {
+    /// Switch to `mode`, easing from `current` over `duration` seconds
+    pub fn set_mode(&mut self, mode: WaterLevelMode, current: f32, duration: f32, ease: EaseFunction) {
+        self.mode = mode;
+        self.elapsed = 0.0;
+        self.blend = (duration > 0.0).then_some(WaterLevelBlend {
+            from: current,
+            elapsed: 0.0,
+            duration,
+            ease,
+        });
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.blend.is_some()
+    }
+
+    fn advance(&mut self, dt: f32) -> Option<f32> {
+        self.elapsed += dt;
+        let target = self.mode.sample(self.elapsed)?;
+
+        let Some(blend) = self.blend.as_mut() else {
+            return Some(target);
+        };
+        blend.elapsed += dt;
+        let t = (blend.elapsed / blend.duration).clamp(0.0, 1.0);
+        let level = blend.from.lerp(target, blend.ease.sample_clamped(t));
+        if t >= 1.0 {
+            self.blend = None;
+        }
+        Some(level)
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+pub struct WaterLevelPlugin;
+
+impl Plugin for WaterLevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WaterLevelController>()
+            .add_event::<WaterLevelChanged>()
+            .add_event::<WaterLevelCommand>()
+            .add_systems(EguiPrimaryContextPass, water_level_ui_system.in_set(EditorUiSet))
+            .add_systems(Update, (
+                handle_water_level_commands,
+                drive_water_level,
+                emit_water_level_changed,
+            ).chain().before(update_water_level_on_change));
+    }
+}
+
+fn handle_water_level_commands(
+    mut commands: EventReader<WaterLevelCommand>,
+    mut controller: ResMut<WaterLevelController>,
+    render_cfg: Res<GpuHeightmapRenderConfig>,
+) {
+    for command in commands.read() {
+        let current = render_cfg.water_level_offset;
+        let blend = controller.mode_blend_duration;
+        match command.clone() {
+            WaterLevelCommand::TransitionTo { level, duration, ease } => {
+                controller.set_mode(WaterLevelMode::Hold(level), current, duration, ease);
+            }
+            WaterLevelCommand::StartTide { base, amplitude, period } => {
+                controller.set_mode(
+                    WaterLevelMode::Tide { base, amplitude, period },
+                    current,
+                    blend,
+                    EaseFunction::SmoothStep,
+                );
+            }
+            WaterLevelCommand::PlayKeyframes { keys, looping } => {
+                controller.set_mode(
+                    WaterLevelMode::Keyframes { keys, looping },
+                    current,
+                    blend,
+                    EaseFunction::SmoothStep,
+                );
+            }
+            WaterLevelCommand::Stop => {
+                controller.set_mode(WaterLevelMode::Manual, current, 0.0, EaseFunction::Linear);
+            }
+        }
+    }
+}
+
+fn drive_water_level(
+    time: Res<Time>,
+    mut controller: ResMut<WaterLevelController>,
+    mut render_cfg: ResMut<GpuHeightmapRenderConfig>,
+) {
+    if controller.paused {
+        return;
+    }
+    let Some(level) = controller.advance(time.delta_secs()) else {
+        return;
+    };
+    // Avoid tripping change detection on the config while holding still
+    if (render_cfg.water_level_offset - level).abs() > f32::EPSILON {
+        render_cfg.water_level_offset = level;
+    }
+}
+
+fn emit_water_level_changed(
+    render_cfg: Res<GpuHeightmapRenderConfig>,
+    mut controller: ResMut<WaterLevelController>,
+    mut changed: EventWriter<WaterLevelChanged>,
+) {
+    let level = render_cfg.water_level_offset;
+    match controller.last_level {
+        Some(previous) if (previous - level).abs() > 0.001 => {
+            changed.write(WaterLevelChanged { previous, level });
+            controller.last_level = Some(level);
+        }
+        None => controller.last_level = Some(level),
+        _ => {}
+    }
+}
+
+fn water_level_ui_system(
+    mut contexts: EguiContexts,
+    mut controller: ResMut<WaterLevelController>,
+    render_cfg: Res<GpuHeightmapRenderConfig>,
+    mut commands: EventWriter<WaterLevelCommand>,
+) -> Result<(), BevyError> {
+    let ctx = contexts.ctx_mut()?;
+    let level = render_cfg.water_level_offset;
+    egui::Window::new("Water Level")
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            let mode = match controller.mode {
+                WaterLevelMode::Manual => "Manual",
+                WaterLevelMode::Hold(_) => "Hold",
+                WaterLevelMode::Tide { .. } => "Tide",
+                WaterLevelMode::Keyframes { .. } => "Keyframes",
+            };
+            ui.label(format!("Mode: {mode}  Level: {level:.2}"));
+            if controller.is_transitioning() {
+                ui.label("Transitioning...");
+            }
+            ui.checkbox(&mut controller.paused, "Paused");
+            ui.add(egui::Slider::new(&mut controller.mode_blend_duration, 0.0..=10.0).text("Mode Blend (s)"));
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("Flood +10").clicked() {
+                    commands.write(WaterLevelCommand::TransitionTo {
+                        level: level + 10.0,
+                        duration: 8.0,
+                        ease: EaseFunction::CubicInOut,
+                    });
+                }
+                if ui.button("Drain -10").clicked() {
+                    commands.write(WaterLevelCommand::TransitionTo {
+                        level: level - 10.0,
+                        duration: 8.0,
+                        ease: EaseFunction::CubicInOut,
+                    });
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Start Tide").clicked() {
+                    commands.write(WaterLevelCommand::StartTide {
+                        base: level,
+                        amplitude: 3.0,
+                        period: 30.0,
+                    });
+                }
+                if ui.button("Dam Break").clicked() {
+                    // Fast surge, slight overshoot, then a slow settle
+                    commands.write(WaterLevelCommand::PlayKeyframes {
+                        keys: vec![
+                            WaterLevelKeyframe::new(0.0, level, EaseFunction::Linear),
+                            WaterLevelKeyframe::new(4.0, level + 18.0, EaseFunction::QuadraticOut),
+                            WaterLevelKeyframe::new(7.0, level + 14.0, EaseFunction::SineInOut),
+                            WaterLevelKeyframe::new(20.0, level + 15.0, EaseFunction::SmoothStep),
+                        ],
+                        looping: false,
+                    });
+                }
+                if ui.button("Stop").clicked() {
+                    commands.write(WaterLevelCommand::Stop);
+                }
+            });
+        });
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use std::marker::PhantomData;
+
+use bevy::asset::LoadedFolder;
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde::Deserialize;
+
+use crate::data::RonAssetPlugin;
+
+const WATER_PRESET_FOLDER: &str = "presets/water";
+
+/* ------------------------------- Assets ------------------------------- */
+
+/// Water look loaded from `assets/presets/water/*.water.ron`.
+/// Values left out of the file are not touched when the preset is applied.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct WaterPreset {
+    pub name: String,
+    #[serde(default)]
+    pub values: WaterPresetValues,
+}
+
+/// Every value a water preset can drive. Shared by all water config resources,
+/// each of which maps the values it knows about in `WaterPresetTarget`.
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct WaterPresetValues {
+    pub wave_amplitude: Option<f32>,
+    pub wave_frequency: Option<f32>,
+    pub wave_speed: Option<f32>,
+    pub wave_steepness: Option<f32>,
+    pub foam_intensity: Option<f32>,
+    pub foam_cutoff: Option<f32>,
+    pub water_clarity: Option<f32>,
+    pub transparency: Option<f32>,
+    pub reflectance: Option<f32>,
+    pub roughness: Option<f32>,
+    pub refraction_strength: Option<f32>,
+    pub caustic_intensity: Option<f32>,
+    pub caustic_scale: Option<f32>,
+    pub caustic_speed: Option<f32>,
+    pub caustic_depth_fade: Option<f32>,
+}
+
+impl WaterPresetValues {
+    /// Values `t` of the way from `self` to `to`. Only values set in `to` are kept,
+    /// missing starting values jump straight to the target.
+    pub fn blend(&self, to: &Self, t: f32) -> Self {
+        let mix = |from: Option<f32>, to: Option<f32>| {
+            to.map(|to| from.map_or(to, |from| from.lerp(to, t)))
+        };
+        Self {
+            wave_amplitude: mix(self.wave_amplitude, to.wave_amplitude),
+            wave_frequency: mix(self.wave_frequency, to.wave_frequency),
+            wave_speed: mix(self.wave_speed, to.wave_speed),
+            wave_steepness: mix(self.wave_steepness, to.wave_steepness),
+            foam_intensity: mix(self.foam_intensity, to.foam_intensity),
+            foam_cutoff: mix(self.foam_cutoff, to.foam_cutoff),
+            water_clarity: mix(self.water_clarity, to.water_clarity),
+            transparency: mix(self.transparency, to.transparency),
+            reflectance: mix(self.reflectance, to.reflectance),
+            roughness: mix(self.roughness, to.roughness),
+            refraction_strength: mix(self.refraction_strength, to.refraction_strength),
+            caustic_intensity: mix(self.caustic_intensity, to.caustic_intensity),
+            caustic_scale: mix(self.caustic_scale, to.caustic_scale),
+            caustic_speed: mix(self.caustic_speed, to.caustic_speed),
+            caustic_depth_fade: mix(self.caustic_depth_fade, to.caustic_depth_fade),
+        }
+    }
+}
+
+/// Implemented by water config resources that presets can drive
+pub trait WaterPresetTarget: Resource {
+    /// Snapshot of the current values, used as the start of a blend
+    fn preset_values(&self) -> WaterPresetValues;
+    /// Apply every value that is set, leave the rest alone
+    fn apply_preset_values(&mut self, values: &WaterPresetValues);
+}
+
+/* ------------------------------- Events ------------------------------- */
+
+/// Blend every water config towards the preset called `name` over `duration` seconds (0 = snap)
+#[derive(Event, Clone, Debug)]
+pub struct ApplyWaterPreset {
+    pub name: String,
+    pub duration: f32,
+}
+
+/* ----------------------------- Resources ------------------------------ */
+
+/// All presets found in the preset folder
+#[derive(Resource)]
+pub struct WaterPresetLibrary {
+    folder: Handle<LoadedFolder>,
+    /// Blend duration used by the preset buttons
+    pub blend_duration: f32,
+}
+
+impl WaterPresetLibrary {
+    /// Loaded presets, sorted by name
+    pub fn presets<'a>(
+        &self,
+        folders: &'a Assets<LoadedFolder>,
+        presets: &'a Assets<WaterPreset>,
+    ) -> Vec<&'a WaterPreset> {
+        let Some(folder) = folders.get(&self.folder) else {
+            return Vec::new();
+        };
+        let mut found: Vec<&WaterPreset> = folder
+            .handles
+            .iter()
+            .filter_map(|handle| handle.clone().try_typed::<WaterPreset>().ok())
+            .filter_map(|handle| presets.get(&handle))
+            .collect();
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+        found
+    }
+
+    pub fn find<'a>(
+        &self,
+        name: &str,
+        folders: &'a Assets<LoadedFolder>,
+        presets: &'a Assets<WaterPreset>,
+    ) -> Option<&'a WaterPreset> {
+        self.presets(folders, presets)
+            .into_iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Blend in progress for one config type
+#[derive(Resource)]
+struct WaterPresetBlend<T> {
+    from: WaterPresetValues,
+    to: WaterPresetValues,
+    elapsed: f32,
+    duration: f32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/* ------------------------------- Plugins ------------------------------ */
+
+pub struct WaterPresetPlugin;
+
+impl Plugin for WaterPresetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<WaterPreset>::new(&["water.ron"]))
+            .add_event::<ApplyWaterPreset>()
+            .add_systems(Startup, load_water_presets);
+    }
+}
+
+/// Lets `ApplyWaterPreset` drive the config resource `T`
+pub struct WaterPresetTargetPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for WaterPresetTargetPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: WaterPresetTarget> Plugin for WaterPresetTargetPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            start_water_preset_blend::<T>,
+            advance_water_preset_blend::<T>,
+        ).chain());
+    }
+}
+
+fn load_water_presets(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WaterPresetLibrary {
+        folder: asset_server.load_folder(WATER_PRESET_FOLDER),
+        blend_duration: 3.0,
+    });
+}
+
+fn start_water_preset_blend<T: WaterPresetTarget>(
+    mut commands: Commands,
+    mut requests: EventReader<ApplyWaterPreset>,
+    library: Option<Res<WaterPresetLibrary>>,
+    folders: Res<Assets<LoadedFolder>>,
+    presets: Res<Assets<WaterPreset>>,
+    mut config: ResMut<T>,
+) {
+    let Some(library) = library else {
+        requests.clear();
+        return;
+    };
+
+    for request in requests.read() {
+        let Some(preset) = library.find(&request.name, &folders, &presets) else {
+            warn!("Water preset '{}' is not loaded", request.name);
+            continue;
+        };
+
+        if request.duration <= 0.0 {
+            config.apply_preset_values(&preset.values);
+            commands.remove_resource::<WaterPresetBlend<T>>();
+        } else {
+            commands.insert_resource(WaterPresetBlend::<T> {
+                from: config.preset_values(),
+                to: preset.values.clone(),
+                elapsed: 0.0,
+                duration: request.duration,
+                _marker: PhantomData,
+            });
+        }
+    }
+}
+
+fn advance_water_preset_blend<T: WaterPresetTarget>(
+    mut commands: Commands,
+    time: Res<Time>,
+    blend: Option<ResMut<WaterPresetBlend<T>>>,
+    mut config: ResMut<T>,
+) {
+    let Some(mut blend) = blend else {
+        return;
+    };
+
+    blend.elapsed += time.delta_secs();
+    let t = (blend.elapsed / blend.duration).clamp(0.0, 1.0);
+    let eased = EaseFunction::SmoothStep.sample_clamped(t);
+    config.apply_preset_values(&blend.from.blend(&blend.to, eased));
+
+    if t >= 1.0 {
+        commands.remove_resource::<WaterPresetBlend<T>>();
+    }
+}
+
+/// Preset buttons shared by the water config windows
+pub fn water_preset_buttons(
+    ui: &mut egui::Ui,
+    library: &mut WaterPresetLibrary,
+    folders: &Assets<LoadedFolder>,
+    presets: &Assets<WaterPreset>,
+    requests: &mut EventWriter<ApplyWaterPreset>,
+) {
+    ui.add(egui::Slider::new(&mut library.blend_duration, 0.0..=20.0).text("Blend (s)"));
+
+    let loaded = library.presets(folders, presets);
+    if loaded.is_empty() {
+        ui.label(format!("No presets in assets/{WATER_PRESET_FOLDER}"));
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for preset in loaded {
+            if ui.button(&preset.name).clicked() {
+                requests.write(ApplyWaterPreset {
+                    name: preset.name.clone(),
+                    duration: library.blend_duration,
+                });
+            }
+        }
+    });
+}
@@ -0,0 +1,446 @@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_graph::{self, RenderGraph, RenderLabel};
+use bevy::render::render_resource::binding_types::{storage_buffer_read_only_sized, texture_storage_2d};
+use bevy::render::render_resource::{
+    BindGroup, BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, BufferUsages,
+    CachedComputePipelineId, ComputePassDescriptor, ComputePipelineDescriptor, Extent3d,
+    PipelineCache, RawBufferVec, ShaderStages, StorageTextureAccess, TextureDimension,
+    TextureFormat, TextureUsages,
+};
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::texture::GpuImage;
+use bevy::render::{Render, RenderApp, RenderSet};
+use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
+
+use crate::game::EditorUiSet;
+use crate::heightmap_material::{CompleteMaskedRiverWaterMaterial, GpuHeightmapRenderConfig};
+
+const RIPPLE_SHADER_PATH: &str = "shaders/water_ripples.wgsl";
+pub const RIPPLE_TEXTURE_SIZE: u32 = 256;
+const RIPPLE_WORKGROUP_SIZE: u32 = 8;
+const MAX_RIPPLE_IMPULSES: usize = 32;
+
+/* ------------------------------- Events ------------------------------- */
+
+/// Pushes the water surface down (positive strength) or up (negative) around `pos`.
+/// Only the XZ part of `pos` is used; impulses outside the simulated area are dropped.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct WaterImpulse {
+    pub pos: Vec3,
+    pub radius: f32,
+    pub strength: f32,
+}
+
+/* ----------------------------- Components ----------------------------- */
+
+/// Leaves a wake while the entity is within `max_height` above the water level.
+/// `strength` is applied per second and scales down with height.
+#[derive(Component, Clone, Debug)]
+pub struct WaterWakeEmitter {
+    pub radius: f32,
+    pub strength: f32,
+    pub max_height: f32,
+}
+
+impl Default for WaterWakeEmitter {
+    fn default() -> Self {
+        Self {
+            radius: 4.0,
+            strength: 3.0,
+            max_height: 15.0,
+        }
+    }
+}
+
+/// The camera the simulated area stays centred on. Put it on the main view camera,
+/// not on helper cameras such as the river mask capture.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct RippleCamera;
+
+/* ----------------------------- Resources ------------------------------ */
+
+#[derive(Resource)]
+pub struct WaterRippleConfig {
+    pub enabled: bool,
+    /// World size covered by the simulation texture, centred on the camera
+    pub world_size: f32,
+    /// Squared Courant number of the wave equation, must stay below 0.5
+    pub propagation: f32,
+    pub damping: f32,
+    pub height_scale: f32,
+}
+
+impl Default for WaterRippleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            world_size: 256.0,
+            propagation: 0.35,
+            damping: 0.985,
+            height_scale: 1.0,
+        }
+    }
+}
+
+/// Main-world simulation state, extracted to the render world every frame.
+/// `textures[0]` always holds the latest heights (.x current, .y previous),
+/// because each frame runs an even number of ping-pong steps.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct WaterRippleSim {
+    pub textures: [Handle<Image>; 2],
+    pub origin: Vec2,
+    pub texel_shift: IVec2,
+    pub impulses: Vec<Vec4>,
+    pub propagation: f32,
+    pub damping: f32,
+    pub enabled: bool,
+}
+
+impl WaterRippleSim {
+    pub fn texel_size(world_size: f32) -> f32 {
+        world_size / RIPPLE_TEXTURE_SIZE as f32
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct WaterRippleLabel;
+
+pub struct WaterRipplePlugin;
+
+impl Plugin for WaterRipplePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WaterImpulse>()
+            .init_resource::<WaterRippleConfig>()
+            .add_plugins(ExtractResourcePlugin::<WaterRippleSim>::default())
+            .add_systems(Startup, setup_water_ripple_textures)
+            .add_systems(EguiPrimaryContextPass, water_ripple_ui_system.in_set(EditorUiSet))
+            .add_systems(Update, (
+                emit_wake_impulses,
+                update_water_ripple_sim,
+                sync_ripple_to_water_materials,
+            ).chain());
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .init_resource::<RippleStepBuffers>()
+            .add_systems(Render, prepare_ripple_bind_groups.in_set(RenderSet::PrepareBindGroups));
+
+        let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
+        render_graph.add_node(WaterRippleLabel, WaterRippleNode::default());
+        render_graph.add_node_edge(WaterRippleLabel, bevy::render::graph::CameraDriverLabel);
+    }
+
+    fn finish(&self, app: &mut App) {
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app.init_resource::<WaterRipplePipeline>();
+    }
+}
+
+/* ---------------------------- Main World ------------------------------ */
+
+fn setup_water_ripple_textures(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    config: Res<WaterRippleConfig>,
+) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: RIPPLE_TEXTURE_SIZE,
+            height: RIPPLE_TEXTURE_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0u8; 16],
+        TextureFormat::Rgba32Float,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING;
+
+    commands.insert_resource(WaterRippleSim {
+        textures: [images.add(image.clone()), images.add(image)],
+        origin: Vec2::ZERO,
+        texel_shift: IVec2::ZERO,
+        impulses: Vec::new(),
+        propagation: config.propagation,
+        damping: config.damping,
+        enabled: config.enabled,
+    });
+}
+
+fn emit_wake_impulses(
+    time: Res<Time>,
+    render_cfg: Res<GpuHeightmapRenderConfig>,
+    emitters: Query<(&GlobalTransform, &WaterWakeEmitter)>,
+    mut impulses: EventWriter<WaterImpulse>,
+) {
+    let dt = time.delta_secs();
+    for (transform, wake) in emitters.iter() {
+        let pos = transform.translation();
+        let height = pos.y - render_cfg.water_level_offset;
+        if height < 0.0 || height > wake.max_height {
+            continue;
+        }
+
+        let falloff = 1.0 - height / wake.max_height;
+        impulses.write(WaterImpulse {
+            pos,
+            radius: wake.radius * (1.0 + (1.0 - falloff)),
+            strength: wake.strength * falloff * dt,
+        });
+    }
+}
+
+fn update_water_ripple_sim(
+    config: Res<WaterRippleConfig>,
+    sim: Option<ResMut<WaterRippleSim>>,
+    camera_query: Query<&GlobalTransform, With<RippleCamera>>,
+    mut impulse_events: EventReader<WaterImpulse>,
+) {
+    let Some(mut sim) = sim else {
+        return;
+    };
+
+    sim.enabled = config.enabled;
+    sim.propagation = config.propagation.clamp(0.0, 0.5);
+    sim.damping = config.damping;
+    sim.impulses.clear();
+
+    // Keep the simulated area centred on the camera, snapped to whole texels so
+    // the compute pass can scroll the heights without resampling them
+    let texel = WaterRippleSim::texel_size(config.world_size);
+    if let Ok(camera) = camera_query.single() {
+        let cam = camera.translation().xz();
+        let snapped = (cam / texel).round();
+        let old = (sim.origin / texel).round();
+        sim.texel_shift = (snapped - old).as_ivec2();
+        sim.origin = snapped * texel;
+    } else {
+        sim.texel_shift = IVec2::ZERO;
+    }
+
+    let half = RIPPLE_TEXTURE_SIZE as f32 * 0.5;
+    for impulse in impulse_events.read() {
+        if sim.impulses.len() >= MAX_RIPPLE_IMPULSES {
+            break;
+        }
+        let local = (impulse.pos.xz() - sim.origin) / texel + Vec2::splat(half);
+        let radius = (impulse.radius / texel).max(1.0);
+        if local.x < -radius
+            || local.y < -radius
+            || local.x > RIPPLE_TEXTURE_SIZE as f32 + radius
+            || local.y > RIPPLE_TEXTURE_SIZE as f32 + radius
+        {
+            continue;
+        }
+        sim.impulses.push(Vec4::new(local.x, local.y, radius, impulse.strength));
+    }
+}
+
+fn sync_ripple_to_water_materials(
+    config: Res<WaterRippleConfig>,
+    sim: Option<Res<WaterRippleSim>>,
+    mut materials: ResMut<Assets<CompleteMaskedRiverWaterMaterial>>,
+) {
+    let Some(sim) = sim else {
+        return;
+    };
+
+    // .z = 0 disables ripple sampling in the shader
+    let ripple_params = if config.enabled {
+        Vec4::new(sim.origin.x, sim.origin.y, config.world_size, config.height_scale)
+    } else {
+        Vec4::ZERO
+    };
+
+    for (_, mat) in materials.iter_mut() {
+        if mat.extension.ripple_texture != sim.textures[0] {
+            mat.extension.ripple_texture = sim.textures[0].clone();
+        }
+        mat.extension.ripple_params = ripple_params;
+    }
+}
+
+fn water_ripple_ui_system(
+    mut contexts: EguiContexts,
+    mut config: ResMut<WaterRippleConfig>,
+) -> Result<(), BevyError> {
+    let ctx = contexts.ctx_mut()?;
+    egui::Window::new("Water Ripples")
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            ui.checkbox(&mut config.enabled, "Enable Ripple Simulation");
+            ui.add(egui::Slider::new(&mut config.world_size, 64.0..=1024.0).text("Simulated Area"));
+            ui.add(egui::Slider::new(&mut config.propagation, 0.05..=0.5).text("Propagation"));
+            ui.add(egui::Slider::new(&mut config.damping, 0.9..=0.999).text("Damping"));
+            ui.add(egui::Slider::new(&mut config.height_scale, 0.0..=5.0).text("Height Scale"));
+        });
+
+    Ok(())
+}
+
+/* ---------------------------- Render World ---------------------------- */
+
+/// Per-step parameters, packed as vec4s to match `RippleStep` in the shader:
+/// [0] = (propagation, damping, impulse count, unused), [1] = texel shift,
+/// [2..] = impulses (texel x, texel y, radius in texels, strength)
+const RIPPLE_STEP_LEN: usize = 2 + MAX_RIPPLE_IMPULSES;
+
+/// One buffer per ping-pong step; only the first step scrolls and injects impulses
+#[derive(Resource)]
+struct RippleStepBuffers {
+    steps: [RawBufferVec<Vec4>; 2],
+}
+
+impl Default for RippleStepBuffers {
+    fn default() -> Self {
+        Self {
+            steps: [
+                RawBufferVec::new(BufferUsages::STORAGE),
+                RawBufferVec::new(BufferUsages::STORAGE),
+            ],
+        }
+    }
+}
+
+#[derive(Resource)]
+struct RippleBindGroups([BindGroup; 2]);
+
+#[derive(Resource)]
+struct WaterRipplePipeline {
+    layout: BindGroupLayout,
+    simulate_pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for WaterRipplePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "water_ripple_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::ReadOnly),
+                    texture_storage_2d(TextureFormat::Rgba32Float, StorageTextureAccess::WriteOnly),
+                    storage_buffer_read_only_sized(false, None),
+                ),
+            ),
+        );
+        let shader = world.load_asset(RIPPLE_SHADER_PATH);
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let simulate_pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("water_ripple_simulate".into()),
+            layout: vec![layout.clone()],
+            push_constant_ranges: Vec::new(),
+            shader,
+            shader_defs: vec![],
+            entry_point: Cow::from("simulate"),
+            zero_initialize_workgroup_memory: false,
+        });
+
+        Self {
+            layout,
+            simulate_pipeline,
+        }
+    }
+}
+
+fn prepare_ripple_bind_groups(
+    mut commands: Commands,
+    pipeline: Res<WaterRipplePipeline>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    sim: Option<Res<WaterRippleSim>>,
+    mut buffers: ResMut<RippleStepBuffers>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let Some(sim) = sim else {
+        return;
+    };
+    let (Some(view_a), Some(view_b)) = (
+        gpu_images.get(&sim.textures[0]),
+        gpu_images.get(&sim.textures[1]),
+    ) else {
+        return;
+    };
+
+    for (step, buffer) in buffers.steps.iter_mut().enumerate() {
+        buffer.clear();
+        // Only the first step scrolls the area and injects this frame's impulses
+        let impulse_count = if step == 0 { sim.impulses.len() } else { 0 };
+        buffer.push(Vec4::new(sim.propagation, sim.damping, impulse_count as f32, 0.0));
+        let shift = if step == 0 { sim.texel_shift.as_vec2() } else { Vec2::ZERO };
+        buffer.push(Vec4::new(shift.x, shift.y, 0.0, 0.0));
+        for i in 0..MAX_RIPPLE_IMPULSES {
+            let impulse = sim.impulses.get(i).filter(|_| step == 0).copied();
+            buffer.push(impulse.unwrap_or(Vec4::ZERO));
+        }
+        debug_assert_eq!(buffer.len(), RIPPLE_STEP_LEN);
+        buffer.write_buffer(&render_device, &render_queue);
+    }
+
+    let (Some(step_a), Some(step_b)) = (
+        buffers.steps[0].binding(),
+        buffers.steps[1].binding(),
+    ) else {
+        return;
+    };
+
+    let a_to_b = render_device.create_bind_group(
+        Some("water_ripple_a_to_b"),
+        &pipeline.layout,
+        &BindGroupEntries::sequential((&view_a.texture_view, &view_b.texture_view, step_a)),
+    );
+    let b_to_a = render_device.create_bind_group(
+        Some("water_ripple_b_to_a"),
+        &pipeline.layout,
+        &BindGroupEntries::sequential((&view_b.texture_view, &view_a.texture_view, step_b)),
+    );
+    commands.insert_resource(RippleBindGroups([a_to_b, b_to_a]));
+}
+
+#[derive(Default)]
+struct WaterRippleNode;
+
+impl render_graph::Node for WaterRippleNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let Some(sim) = world.get_resource::<WaterRippleSim>() else {
+            return Ok(());
+        };
+        let Some(bind_groups) = world.get_resource::<RippleBindGroups>() else {
+            return Ok(());
+        };
+        if !sim.enabled {
+            return Ok(());
+        }
+
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = world.resource::<WaterRipplePipeline>();
+        let Some(simulate) = pipeline_cache.get_compute_pipeline(pipeline.simulate_pipeline) else {
+            return Ok(());
+        };
+
+        let groups = RIPPLE_TEXTURE_SIZE / RIPPLE_WORKGROUP_SIZE;
+        let mut pass = render_context
+            .command_encoder()
+            .begin_compute_pass(&ComputePassDescriptor::default());
+        pass.set_pipeline(simulate);
+        for bind_group in bind_groups.0.iter() {
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(groups, groups, 1);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+
+use crate::heightmap_material::{GpuHeightmapConfigUI, GpuHeightmapRenderConfig, MaskedRiverWaterConfig};
+
+/// CPU mirror of the masked river water surface (`masked_river_water.wgsl`).
+/// Gameplay uses it to ask "where is the water surface here?" without reading back from the GPU.
+#[derive(Clone, Debug)]
+pub struct WaterSurfaceSampler {
+    pub water_level: f32,
+    // .x amp .y freq .z speed .w steepness
+    pub wave_params: Vec4,
+    pub river_width: f32,
+    pub bank_slope_distance: f32,
+    pub meander_frequency: f32,
+    pub meander_amplitude: f32,
+    pub river_start: Vec2,
+    pub river_dir: Vec2,
+}
+
+impl WaterSurfaceSampler {
+    pub fn new(
+        render_cfg: &GpuHeightmapRenderConfig,
+        water_cfg: &MaskedRiverWaterConfig,
+        height_cfg: &GpuHeightmapConfigUI,
+    ) -> Self {
+        Self {
+            water_level: render_cfg.water_level_offset,
+            wave_params: Vec4::new(
+                water_cfg.wave_amplitude,
+                water_cfg.wave_frequency,
+                water_cfg.wave_speed,
+                water_cfg.wave_steepness,
+            ),
+            river_width: height_cfg.river_width,
+            bank_slope_distance: height_cfg.bank_slope_distance,
+            meander_frequency: height_cfg.meander_frequency,
+            meander_amplitude: height_cfg.meander_amplitude,
+            river_start: Vec2::new(height_cfg.river_start_x, height_cfg.river_start_y),
+            river_dir: Vec2::new(height_cfg.river_dir_x, height_cfg.river_dir_y).normalize_or(Vec2::X),
+        }
+    }
+
+    /// World height of the displaced water surface at `pos` (XZ)
+    pub fn surface_height(&self, pos: Vec2, time: f32) -> f32 {
+        self.water_level + noise_wave_height(pos, time, self.wave_params)
+    }
+
+    /// Distance from the river centre line and distance along the river
+    pub fn river_dist_and_along(&self, pos: Vec2) -> (f32, f32) {
+        let along = (pos - self.river_start).dot(self.river_dir);
+        let perpendicular = Vec2::new(-self.river_dir.y, self.river_dir.x);
+        let center = self.river_start
+            + self.river_dir * along
+            + perpendicular * self.meander(along);
+        (pos.distance(center), along)
+    }
+
+    /// True where the water plane is drawn (inside the river banks)
+    pub fn is_over_water(&self, pos: Vec2) -> bool {
+        let (dist, along) = self.river_dist_and_along(pos);
+        let width = self.river_width * (1.0 + width_noise(along));
+        dist <= width * 0.5 + self.bank_slope_distance
+    }
+
+    // Same simplified meander as the water shader
+    fn meander(&self, along: f32) -> f32 {
+        let tau = std::f32::consts::TAU;
+        let phase = along * self.meander_frequency;
+
+        let primary = (phase * tau).sin();
+        let secondary = (along * self.meander_frequency * 1.7 * tau).sin() * 0.4;
+        let chaos = (phase * 0.37).sin() * 0.3;
+        let scale_variation = (phase * 0.3).sin() * 0.2;
+        let asymmetry = (phase * 0.8 + 1.57).sin() * 0.2;
+
+        let base = primary * 0.7 + secondary * 0.3;
+        let total = (base + chaos * 0.6 * 0.5 + asymmetry * 0.2) * (1.0 + scale_variation);
+        total * self.meander_amplitude
+    }
+}
+
+fn width_noise(along: f32) -> f32 {
+    ((along * 0.0005).sin() * 0.5 + 0.5) * 0.3
+}
+
+/// Height part of `get_noise_wave` in the water shader
+pub fn noise_wave_height(pos: Vec2, time: f32, wave_params: Vec4) -> f32 {
+    let amplitude = wave_params.x;
+    let frequency = wave_params.y * 0.1;
+    let speed = wave_params.z;
+    let octaves = (wave_params.w * 8.0).clamp(2.0, 8.0) as i32;
+
+    let animated = pos * frequency;
+    let offset1 = Vec2::new(time * speed * 0.3, time * speed * 0.2);
+    let offset2 = Vec2::new(time * speed * 0.1, time * speed * 0.4);
+
+    let noise1 = fbm(animated + offset1, octaves);
+    let noise2 = fbm(animated * 0.7 + offset2, (octaves - 2).max(2));
+
+    (noise1 * 0.7 + noise2 * 0.3) * amplitude * 3.0
+}
+
+pub fn fbm(pos: Vec2, octaves: i32) -> f32 {
+    let mut value = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    for _ in 0..octaves {
+        value += amplitude * simplex2d(pos * frequency);
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    value
+}
+
+fn mod289(x: Vec3) -> Vec3 {
+    x - (x * (1.0 / 289.0)).floor() * 289.0
+}
+
+fn permute(x: Vec3) -> Vec3 {
+    mod289(((x * 34.0) + Vec3::ONE) * x)
+}
+
+fn fract3(x: Vec3) -> Vec3 {
+    x - x.floor()
+}
+
+/// Port of `simplex2d` shared by the water shaders
+pub fn simplex2d(v: Vec2) -> f32 {
+    const C: Vec4 = Vec4::new(0.211324865405187, 0.366025403784439, -0.577350269189626, 0.024390243902439);
+
+    let mut i = (v + Vec2::splat(v.dot(Vec2::splat(C.y)))).floor();
+    let x0 = v - i + Vec2::splat(i.dot(Vec2::splat(C.x)));
+
+    let i1 = if x0.x > x0.y { Vec2::new(1.0, 0.0) } else { Vec2::new(0.0, 1.0) };
+    let x1 = x0 + Vec2::splat(C.x) - i1;
+    let x2 = x0 + Vec2::splat(C.z);
+
+    i = i - (i * (1.0 / 289.0)).floor() * 289.0;
+    let p = permute(
+        permute(Vec3::splat(i.y) + Vec3::new(0.0, i1.y, 1.0)) + Vec3::splat(i.x) + Vec3::new(0.0, i1.x, 1.0),
+    );
+
+    let mut m = (Vec3::splat(0.5) - Vec3::new(x0.dot(x0), x1.dot(x1), x2.dot(x2))).max(Vec3::ZERO);
+    m = m * m;
+    m = m * m;
+
+    let x = 2.0 * fract3(p * C.w) - Vec3::ONE;
+    let h = x.abs() - Vec3::splat(0.5);
+    let ox = (x + Vec3::splat(0.5)).floor();
+    let a0 = x - ox;
+
+    m *= Vec3::splat(1.79284291400159) - 0.85373472095314 * (a0 * a0 + h * h);
+
+    let g = Vec3::new(
+        a0.x * x0.x + h.x * x0.y,
+        a0.y * x1.x + h.y * x1.y,
+        a0.z * x2.x + h.z * x2.y,
+    );
+
+    130.0 * m.dot(g)
+}
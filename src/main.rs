@@ -1,6 +1,9 @@
+mod data;
+mod game;
 mod rendering;
 mod flyby;
 mod heightmap_material;
+mod paths;
 
 use bevy::prelude::*;
 use bevy_blendy_cameras::BlendyCamerasPlugin;
@@ -8,9 +11,19 @@ use bevy_blendy_cameras::FlyCameraController;
 use bevy_blendy_cameras::OrbitCameraController;
 use bevy_egui::EguiPlugin;
 use crate::flyby::RiverRaidCamera;
+use crate::game::{BombPlugin, BossPlugin, BridgePlugin, CollisionPlugin, CrashPlugin, EnemyPlugin, FlightPlugin, FormationPlugin, FuelPlugin, GameState, GameStatePlugin, HudPlugin, LivesPlugin, ScorePlugin, SteeringPlugin, TargetingPlugin, TurretPlugin, WavePlugin, WeaponPlugin};
 use crate::heightmap_material::GpuHeightmapRendererPlugin;
 use crate::heightmap_material::GpuHeightmapTerrainPlugin;
 use crate::heightmap_material::MaskedRiverWaterPlugin;
+use crate::heightmap_material::RippleCamera;
+use crate::heightmap_material::UnderwaterPlugin;
+use crate::heightmap_material::WaterLevelPlugin;
+use crate::heightmap_material::WaterRipplePlugin;
+use crate::paths::PathPlugin;
+use crate::rendering::animation::AnimationPlugin;
+use crate::rendering::bullet::BulletPlugin;
+use crate::rendering::input::InputPlugin;
+use crate::rendering::plane::PlanePlugin;
 
 use bevy::input::keyboard::KeyCode;
 
@@ -28,12 +41,38 @@ fn main() {
         setup_camera_and_light,
     ))
     .add_systems(Update, (
-        camera_controls,
+        camera_controls.run_if(in_state(GameState::Editor)),
     ))
     .add_plugins(EguiPlugin::default())
+    .add_plugins(GameStatePlugin)
     .add_plugins(MaskedRiverWaterPlugin)
     .add_plugins(GpuHeightmapTerrainPlugin)
     .add_plugins(GpuHeightmapRendererPlugin)
+    .add_plugins(WaterLevelPlugin)
+    .add_plugins(WaterRipplePlugin)
+    .add_plugins(UnderwaterPlugin)
+    .add_plugins(PlanePlugin)
+    .add_plugins(FlightPlugin)
+    .add_plugins(FuelPlugin)
+    .add_plugins(CollisionPlugin)
+    .add_plugins(BridgePlugin)
+    .add_plugins(CrashPlugin)
+    .add_plugins(ScorePlugin)
+    .add_plugins(LivesPlugin)
+    .add_plugins(WeaponPlugin)
+    .add_plugins(BombPlugin)
+    .add_plugins(EnemyPlugin)
+    .add_plugins(WavePlugin)
+    .add_plugins(FormationPlugin)
+    .add_plugins(SteeringPlugin)
+    .add_plugins(TargetingPlugin)
+    .add_plugins(TurretPlugin)
+    .add_plugins(BossPlugin)
+    .add_plugins(HudPlugin)
+    .add_plugins(InputPlugin)
+    .add_plugins(AnimationPlugin)
+    .add_plugins(BulletPlugin)
+    .add_plugins(PathPlugin)
     .add_plugins(BlendyCamerasPlugin);
     // .add_plugins(FlyByPlugin)
     app.run();
@@ -93,6 +132,7 @@ pub fn setup_camera_and_light(mut commands: Commands) {
             speed: 100.0,
             ..default()
         },  
+        RippleCamera,
     ));
 
     // Light
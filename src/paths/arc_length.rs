@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::paths::{rotation_minimising_ups, Path};
+
+/// Samples taken along a path to build its arc-length table
+const ARC_LENGTH_SAMPLES: usize = 256;
+
+/// A path with an arc-length lookup table, so it can be queried by distance
+/// travelled instead of by curve parameter
+pub struct MeasuredPath {
+    path: Arc<dyn Path>,
+    /// Distance travelled at `t = i / (len - 1)`
+    distances: Vec<f32>,
+    /// Rotation-minimising up vector at the same samples
+    pub(crate) ups: Vec<Vec3>,
+}
+
+impl MeasuredPath {
+    pub fn new(path: Arc<dyn Path>) -> Self {
+        let samples: Vec<f32> = (0..=ARC_LENGTH_SAMPLES)
+            .map(|i| i as f32 / ARC_LENGTH_SAMPLES as f32)
+            .collect();
+        let points: Vec<Vec3> = samples.iter().map(|&t| path.position(t)).collect();
+        let tangents: Vec<Vec3> = samples.iter().map(|&t| path.tangent(t)).collect();
+
+        let mut travelled = 0.0;
+        let distances = std::iter::once(0.0)
+            .chain(points.windows(2).map(|pair| {
+                travelled += pair[0].distance(pair[1]);
+                travelled
+            }))
+            .collect();
+        let ups = rotation_minimising_ups(&points, &tangents);
+        Self { path, distances, ups }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.distances.last().copied().unwrap_or(0.0)
+    }
+
+    /// Curve parameter reached after travelling `distance` from the start
+    pub fn t_at_distance(&self, distance: f32) -> f32 {
+        let length = self.length();
+        if length <= 0.0 {
+            return 0.0;
+        }
+        let distance = distance.clamp(0.0, length);
+
+        // First sample at or beyond `distance`, then interpolate within the step before it
+        let upper = self.distances.partition_point(|&d| d < distance).clamp(1, self.distances.len() - 1);
+        let (d0, d1) = (self.distances[upper - 1], self.distances[upper]);
+        let fraction = if d1 > d0 { (distance - d0) / (d1 - d0) } else { 0.0 };
+        (upper as f32 - 1.0 + fraction) / (self.distances.len() - 1) as f32
+    }
+
+    pub fn position_at_distance(&self, distance: f32) -> Vec3 {
+        self.path.position(self.t_at_distance(distance))
+    }
+
+    pub fn tangent_at_distance(&self, distance: f32) -> Vec3 {
+        self.path.tangent(self.t_at_distance(distance))
+    }
+}
+
+/// Speed along a path as multipliers of the follower's base speed, keyed by the
+/// fraction of the path travelled. Linear in between, flat past either end.
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(transparent)]
+pub struct SpeedProfile(pub Vec<(f32, f32)>);
+
+impl SpeedProfile {
+    pub fn multiplier(&self, fraction: f32) -> f32 {
+        let keys = &self.0;
+        let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+            return 1.0;
+        };
+        if fraction <= first.0 {
+            return first.1;
+        }
+        if fraction >= last.0 {
+            return last.1;
+        }
+
+        let upper = keys.partition_point(|&(at, _)| at < fraction).clamp(1, keys.len() - 1);
+        let (a, b) = (keys[upper - 1], keys[upper]);
+        let blend = if b.0 > a.0 { (fraction - a.0) / (b.0 - a.0) } else { 1.0 };
+        a.1.lerp(b.1, blend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::PolylinePath;
+
+    fn straight(length: f32) -> MeasuredPath {
+        MeasuredPath::new(Arc::new(PolylinePath::new(vec![Vec3::ZERO, Vec3::X * length])))
+    }
+
+    #[test]
+    fn zero_length_path_stays_at_the_start() {
+        let path = MeasuredPath::new(Arc::new(PolylinePath::new(vec![Vec3::ONE, Vec3::ONE])));
+        assert_eq!(path.length(), 0.0);
+        assert_eq!(path.t_at_distance(5.0), 0.0);
+        assert_eq!(path.position_at_distance(5.0), Vec3::ONE);
+    }
+
+    #[test]
+    fn distance_maps_evenly_along_a_straight_line() {
+        let path = straight(10.0);
+        assert!((path.length() - 10.0).abs() < 1e-3);
+        assert!((path.t_at_distance(5.0) - 0.5).abs() < 1e-3);
+        assert!(path.position_at_distance(2.5).distance(Vec3::X * 2.5) < 1e-3);
+    }
+
+    #[test]
+    fn distance_is_clamped_to_the_path() {
+        let path = straight(10.0);
+        assert_eq!(path.t_at_distance(-3.0), 0.0);
+        assert!((path.t_at_distance(30.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn empty_profile_keeps_base_speed() {
+        assert_eq!(SpeedProfile::default().multiplier(0.5), 1.0);
+    }
+
+    #[test]
+    fn profile_is_flat_past_either_end() {
+        let profile = SpeedProfile(vec![(0.2, 0.5), (0.8, 2.0)]);
+        assert_eq!(profile.multiplier(0.0), 0.5);
+        assert_eq!(profile.multiplier(-1.0), 0.5);
+        assert_eq!(profile.multiplier(1.0), 2.0);
+        assert_eq!(profile.multiplier(3.0), 2.0);
+    }
+
+    #[test]
+    fn profile_interpolates_between_keys() {
+        let profile = SpeedProfile(vec![(0.0, 1.0), (0.5, 3.0), (1.0, 1.0)]);
+        assert!((profile.multiplier(0.25) - 2.0).abs() < 1e-6);
+        assert!((profile.multiplier(0.75) - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn profile_with_repeated_key_jumps_without_nan() {
+        let profile = SpeedProfile(vec![(0.0, 1.0), (0.5, 1.0), (0.5, 4.0), (1.0, 4.0)]);
+        let at_step = profile.multiplier(0.5);
+        assert!(at_step.is_finite());
+        assert_eq!(profile.multiplier(0.6), 4.0);
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// A curve through world space, parameterised by `t` from 0 at the start to 1 at the end
+pub trait Path: Send + Sync + 'static {
+    fn position(&self, t: f32) -> Vec3;
+
+    /// Direction of travel at `t`, normalised
+    fn tangent(&self, t: f32) -> Vec3 {
+        const H: f32 = 1e-3;
+        let (a, b) = if t + H <= 1.0 { (t, t + H) } else { (t - H, t) };
+        (self.position(b) - self.position(a)).normalize_or_zero()
+    }
+}
+
+/// Which curve family to build from a list of points
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CurveKind {
+    /// One Bezier curve of any degree; passes through the first and last point only
+    Bezier,
+    /// Passes through every point
+    #[default]
+    CatmullRom,
+    /// Uniform cubic B-spline, the smoothest; pulled towards the points, clamped to the ends
+    BSpline,
+    /// Straight segments between the points
+    Polyline,
+}
+
+impl CurveKind {
+    pub fn build(self, points: Vec<Vec3>) -> Arc<dyn Path> {
+        match self {
+            CurveKind::Bezier => Arc::new(BezierPath::new(points)),
+            CurveKind::CatmullRom => Arc::new(CatmullRomPath::new(points)),
+            CurveKind::BSpline => Arc::new(BSplinePath::new(points)),
+            CurveKind::Polyline => Arc::new(PolylinePath::new(points)),
+        }
+    }
+}
+
+/// Splits `t` over `segments` equal pieces: (segment index, local t)
+fn segment_at(t: f32, segments: usize) -> (usize, f32) {
+    let scaled = t.clamp(0.0, 1.0) * segments as f32;
+    let index = (scaled.floor() as usize).min(segments.saturating_sub(1));
+    (index, scaled - index as f32)
+}
+
+/* ------------------------------- Bezier ------------------------------- */
+
+pub struct BezierPath {
+    points: Vec<Vec3>,
+}
+
+impl BezierPath {
+    pub fn new(points: Vec<Vec3>) -> Self {
+        Self { points }
+    }
+}
+
+impl Path for BezierPath {
+    fn position(&self, t: f32) -> Vec3 {
+        let Some(&first) = self.points.first() else {
+            return Vec3::ZERO;
+        };
+        let n = self.points.len() - 1;
+        if n == 0 {
+            return first;
+        }
+
+        let t = t.clamp(0.0, 1.0);
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, &point)| {
+                let factor = binomial_coefficient(n, i) as f32 * (1.0 - t).powi((n - i) as i32) * t.powi(i as i32);
+                point * factor
+            })
+            .sum()
+    }
+}
+
+fn binomial_coefficient(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    if k == 0 || k == n {
+        return 1;
+    }
+    if k > n / 2 {
+        return binomial_coefficient(n, n - k);
+    }
+
+    let mut result = 1;
+    for i in 0..k {
+        result = result * (n - i) / (i + 1);
+    }
+    result
+}
+
+/* ----------------------------- Catmull-Rom ---------------------------- */
+
+pub struct CatmullRomPath {
+    points: Vec<Vec3>,
+}
+
+impl CatmullRomPath {
+    pub fn new(points: Vec<Vec3>) -> Self {
+        Self { points }
+    }
+}
+
+impl Path for CatmullRomPath {
+    fn position(&self, t: f32) -> Vec3 {
+        let n = self.points.len();
+        match n {
+            0 => return Vec3::ZERO,
+            1 => return self.points[0],
+            _ => {}
+        }
+
+        let (i, u) = segment_at(t, n - 1);
+        // Repeat the end points so the curve reaches them
+        let p0 = self.points[i.saturating_sub(1)];
+        let p1 = self.points[i];
+        let p2 = self.points[i + 1];
+        let p3 = self.points[(i + 2).min(n - 1)];
+
+        let u2 = u * u;
+        let u3 = u2 * u;
+        0.5 * ((2.0 * p1)
+            + (p2 - p0) * u
+            + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * u2
+            + (3.0 * p1 - p0 - 3.0 * p2 + p3) * u3)
+    }
+}
+
+/* ------------------------------ B-spline ------------------------------ */
+
+pub struct BSplinePath {
+    /// The points with each end repeated so the curve starts and finishes on them
+    clamped: Vec<Vec3>,
+}
+
+impl BSplinePath {
+    pub fn new(points: Vec<Vec3>) -> Self {
+        let mut clamped = Vec::with_capacity(points.len() + 4);
+        if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+            clamped.extend([first, first]);
+            clamped.extend(points.iter().copied());
+            clamped.extend([last, last]);
+        }
+        Self { clamped }
+    }
+}
+
+impl Path for BSplinePath {
+    fn position(&self, t: f32) -> Vec3 {
+        if self.clamped.len() < 4 {
+            return Vec3::ZERO;
+        }
+
+        let (i, u) = segment_at(t, self.clamped.len() - 3);
+        let [p0, p1, p2, p3] = [self.clamped[i], self.clamped[i + 1], self.clamped[i + 2], self.clamped[i + 3]];
+
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let b0 = (1.0 - u).powi(3) / 6.0;
+        let b1 = (3.0 * u3 - 6.0 * u2 + 4.0) / 6.0;
+        let b2 = (-3.0 * u3 + 3.0 * u2 + 3.0 * u + 1.0) / 6.0;
+        let b3 = u3 / 6.0;
+        p0 * b0 + p1 * b1 + p2 * b2 + p3 * b3
+    }
+}
+
+/* ------------------------------ Polyline ------------------------------ */
+
+pub struct PolylinePath {
+    points: Vec<Vec3>,
+}
+
+impl PolylinePath {
+    pub fn new(points: Vec<Vec3>) -> Self {
+        Self { points }
+    }
+}
+
+impl Path for PolylinePath {
+    fn position(&self, t: f32) -> Vec3 {
+        match self.points.len() {
+            0 => Vec3::ZERO,
+            1 => self.points[0],
+            n => {
+                let (i, u) = segment_at(t, n - 1);
+                self.points[i].lerp(self.points[i + 1], u)
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::{CollisionSet, FlightSet, GameState, Velocity, WeaponSet};
+use crate::paths::{Banking, MeasuredPath, SpeedProfile};
+
+/* ----------------------------- Components ----------------------------- */
+
+/// What a follower does when it reaches the end of its path
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PathEnd {
+    #[default]
+    Despawn,
+    /// Stays pinned to the last point. The follower stays attached, so nothing else
+    /// takes over moving the entity.
+    Hold,
+    /// Starts over from the beginning
+    Loop,
+}
+
+/// Moves any entity along a path at a steady speed. Nose (local +Z) follows the tangent,
+/// and with banking on, up follows the path's rotation-minimising frame.
+#[derive(Component, Clone)]
+pub struct PathFollower {
+    pub path: Arc<MeasuredPath>,
+    /// Distance travelled from the start of the path
+    pub distance: f32,
+    /// World units per second, before the profile is applied
+    pub speed: f32,
+    pub profile: SpeedProfile,
+    pub end: PathEnd,
+    /// `None` keeps the follower upright, e.g. for boats and ground vehicles
+    pub banking: Option<Banking>,
+}
+
+impl PathFollower {
+    pub fn new(path: Arc<MeasuredPath>, speed: f32, end: PathEnd) -> Self {
+        Self {
+            path,
+            distance: 0.0,
+            speed,
+            profile: SpeedProfile::default(),
+            end,
+            banking: Some(Banking::default()),
+        }
+    }
+
+    pub fn without_banking(mut self) -> Self {
+        self.banking = None;
+        self
+    }
+
+    pub fn with_profile(mut self, profile: SpeedProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Current speed in world units per second
+    pub fn current_speed(&self) -> f32 {
+        let length = self.path.length();
+        let fraction = if length > 0.0 { self.distance / length } else { 1.0 };
+        self.speed * self.profile.multiplier(fraction)
+    }
+}
+
+/* ------------------------------- Plugin ------------------------------- */
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathSet;
+
+pub struct PathPlugin;
+
+impl Plugin for PathPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_sets(Update, PathSet.after(FlightSet).before(WeaponSet).before(CollisionSet))
+            .add_systems(Update, follow_paths.in_set(PathSet).run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn follow_paths(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut followers: Query<(Entity, &mut PathFollower, &mut Transform, Option<&mut Velocity>)>,
+) {
+    let dt = time.delta_secs();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (entity, mut follower, mut transform, velocity) in followers.iter_mut() {
+        follower.distance += follower.current_speed() * dt;
+
+        let length = follower.path.length();
+        if follower.distance >= length {
+            match follower.end {
+                PathEnd::Despawn => {
+                    commands.entity(entity).despawn();
+                    continue;
+                }
+                PathEnd::Hold => follower.distance = length,
+                PathEnd::Loop => follower.distance = follower.distance.rem_euclid(length.max(f32::EPSILON)),
+            }
+        }
+
+        let position = follower.path.position_at_distance(follower.distance);
+        if let Some(mut velocity) = velocity {
+            velocity.0 = (position - transform.translation) / dt;
+        }
+        transform.translation = position;
+
+        let distance = follower.distance;
+        let tangent = follower.path.tangent_at_distance(distance);
+        if tangent == Vec3::ZERO {
+            continue;
+        }
+
+        let speed = follower.current_speed();
+        let path = follower.path.clone();
+        match follower.banking.as_mut() {
+            Some(banking) => {
+                let up = path.up_at_distance(distance);
+                let right = tangent.cross(up);
+                let roll = banking.update(speed, path.curvature_at_distance(distance).dot(right), dt);
+                // The model's nose is +Z, so roll about +Z tips the right wing down
+                transform.look_to(-tangent, up);
+                transform.rotate_local_z(roll);
+            }
+            None => transform.look_to(-tangent, Vec3::Y),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::paths::MeasuredPath;
+
+/// Up vectors along a sampled curve that twist as little as possible,
+/// by the double reflection method (Wang et al. 2008)
+pub(crate) fn rotation_minimising_ups(points: &[Vec3], tangents: &[Vec3]) -> Vec<Vec3> {
+    let Some(&first_tangent) = tangents.first() else {
+        return Vec::new();
+    };
+    // Start as close to world up as the first tangent allows
+    let mut up = Vec3::Y.reject_from(first_tangent).try_normalize().unwrap_or_else(|| first_tangent.any_orthonormal_vector());
+
+    let mut ups = Vec::with_capacity(points.len());
+    ups.push(up);
+    for i in 0..points.len().saturating_sub(1) {
+        let v1 = points[i + 1] - points[i];
+        let c1 = v1.length_squared();
+        if c1 <= f32::EPSILON {
+            ups.push(up);
+            continue;
+        }
+        let reflected_up = up - (2.0 / c1) * v1.dot(up) * v1;
+        let reflected_tangent = tangents[i] - (2.0 / c1) * v1.dot(tangents[i]) * v1;
+
+        let v2 = tangents[i + 1] - reflected_tangent;
+        let c2 = v2.length_squared();
+        up = if c2 <= f32::EPSILON {
+            reflected_up
+        } else {
+            reflected_up - (2.0 / c2) * v2.dot(reflected_up) * v2
+        };
+        up = up.reject_from(tangents[i + 1]).try_normalize().unwrap_or(up);
+        ups.push(up);
+    }
+    ups
+}
+
+/// Rolls a path follower into its turns
+#[derive(Clone, Copy, Debug)]
+pub struct Banking {
+    /// Radians
+    pub max_angle: f32,
+    /// How quickly the roll catches up with the turn, per second
+    pub smoothing: f32,
+    /// Sets how hard a turn has to be for a given bank: `tan(bank) = lateral acceleration / gravity`
+    pub gravity: f32,
+    /// Current roll in radians, positive to the right
+    pub angle: f32,
+}
+
+impl Default for Banking {
+    fn default() -> Self {
+        Self {
+            max_angle: 60f32.to_radians(),
+            smoothing: 5.0,
+            gravity: 40.0,
+            angle: 0.0,
+        }
+    }
+}
+
+impl Banking {
+    /// Eases the roll towards the bank for a turn of `curvature` (1 / radius, positive
+    /// to the right) taken at `speed`, and returns it
+    pub fn update(&mut self, speed: f32, curvature: f32, dt: f32) -> f32 {
+        let target = (speed * speed * curvature / self.gravity.max(f32::EPSILON))
+            .atan()
+            .clamp(-self.max_angle, self.max_angle);
+        self.angle = self.angle.lerp(target, 1.0 - (-self.smoothing * dt).exp());
+        self.angle
+    }
+}
+
+impl MeasuredPath {
+    /// Rotation-minimising up vector after travelling `distance`
+    pub fn up_at_distance(&self, distance: f32) -> Vec3 {
+        let tangent = self.tangent_at_distance(distance);
+        let last = self.ups.len().saturating_sub(1);
+        let sample = self.t_at_distance(distance) * last as f32;
+        let index = (sample.floor() as usize).min(last.saturating_sub(1));
+        let (Some(&a), Some(&b)) = (self.ups.get(index), self.ups.get(index + 1)) else {
+            return Vec3::Y;
+        };
+        a.lerp(b, sample - index as f32)
+            .reject_from(tangent)
+            .try_normalize()
+            .unwrap_or(Vec3::Y)
+    }
+
+    /// How sharply the path turns after `distance`, as 1 / radius.
+    /// Points from the path towards the centre of the turn.
+    pub fn curvature_at_distance(&self, distance: f32) -> Vec3 {
+        const H: f32 = 0.5;
+        let before = self.tangent_at_distance(distance - H);
+        let after = self.tangent_at_distance(distance + H);
+        (after - before) / (2.0 * H)
+    }
+}
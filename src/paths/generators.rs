@@ -0,0 +1,118 @@
+use std::f32::consts::{PI, TAU};
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::heightmap_material::TerrainSampler;
+use crate::paths::{CatmullRomPath, Path};
+
+/// Rough spacing between the points a shape is sampled at
+const SAMPLE_STEP: f32 = 10.0;
+const MIN_SAMPLES: usize = 8;
+const MAX_SAMPLES: usize = 256;
+/// Sideways step when searching a bank for its highest ground
+const RIDGE_SEARCH_STEP: f32 = 8.0;
+
+/// Path laid out against the river and terrain, starting at a distance along the river.
+/// Lengths are signed: negative runs back towards the player. Laterals are offsets from
+/// the river's centre line, positive to the right of the flight direction; heights are
+/// above the ground or water.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PathShape {
+    /// Down the meandering centre line, `offset` to one side
+    RiverLine { length: f32, offset: f32, height: f32 },
+    /// Starts over the highest ground within `search` of the `side` bank (-1 or 1), drops into
+    /// the channel over the first `dive` fraction of the path and runs down it for the rest
+    RidgeSwoop { side: f32, search: f32, length: f32, dive: f32, height: f32 },
+    /// Weaves from bank to bank, `crossings` times over `length`, covering `width` of the channel
+    Strafe { length: f32, crossings: u32, width: f32, height: f32 },
+    /// Circles a point `lateral` from the centre line
+    Circle { lateral: f32, radius: f32, laps: f32, height: f32 },
+}
+
+impl PathShape {
+    /// Samples the shape starting `along` the river, never less than `clearance` above the surface
+    pub fn points(&self, terrain: &TerrainSampler, water_level: f32, along: f32, clearance: f32) -> Vec<Vec3> {
+        let ridge = match *self {
+            PathShape::RidgeSwoop { side, search, .. } => ridge_lateral(terrain, along, side, search),
+            _ => 0.0,
+        };
+        // (along, lateral from the centre line, height) at `u` from 0 to 1
+        let river_point = |u: f32| -> (f32, f32, f32) {
+            match *self {
+                PathShape::RiverLine { length, offset, height } => (along + u * length, offset, height),
+                PathShape::RidgeSwoop { length, dive, height, .. } => {
+                    let drop = (u / dive.max(0.01)).clamp(0.0, 1.0);
+                    let eased = drop * drop * (3.0 - 2.0 * drop);
+                    (along + u * length, ridge * (1.0 - eased), height)
+                }
+                PathShape::Strafe { length, crossings, width, height } => {
+                    let point_along = along + u * length;
+                    let half_width = terrain.river_width_at(point_along) * 0.5;
+                    let sweep = (u * crossings.max(1) as f32 * PI).cos();
+                    (point_along, sweep * width * half_width, height)
+                }
+                PathShape::Circle { lateral, radius, laps, height } => {
+                    // Starts on the player's side of the centre
+                    let angle = u * laps * TAU;
+                    (along - radius * angle.cos(), lateral + radius * angle.sin(), height)
+                }
+            }
+        };
+        let to_xz = |(point_along, lateral, _): (f32, f32, f32)| {
+            terrain.axis_point(point_along, terrain.meander(point_along) + lateral)
+        };
+
+        // Enough samples to keep them about `SAMPLE_STEP` apart
+        let rough_length: f32 = (0..=MIN_SAMPLES)
+            .map(|i| to_xz(river_point(i as f32 / MIN_SAMPLES as f32)))
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+        let count = ((rough_length / SAMPLE_STEP).ceil() as usize).clamp(MIN_SAMPLES, MAX_SAMPLES);
+
+        let samples: Vec<(Vec2, f32)> = (0..=count)
+            .map(|i| {
+                let point = river_point(i as f32 / count as f32);
+                (to_xz(point), point.2)
+            })
+            .collect();
+        let surfaces: Vec<f32> = samples
+            .iter()
+            .map(|&(xz, _)| terrain.height(xz).max(water_level))
+            .collect();
+
+        samples
+            .iter()
+            .enumerate()
+            .map(|(i, &(xz, height))| {
+                // Clear the neighbours too, so the curve between samples stays off the ground
+                let surface = surfaces[i.saturating_sub(1)..(i + 2).min(surfaces.len())]
+                    .iter()
+                    .copied()
+                    .fold(f32::MIN, f32::max);
+                Vec3::new(xz.x, (surfaces[i] + height).max(surface + clearance), xz.y)
+            })
+            .collect()
+    }
+
+    pub fn build(&self, terrain: &TerrainSampler, water_level: f32, along: f32, clearance: f32) -> Arc<dyn Path> {
+        Arc::new(CatmullRomPath::new(self.points(terrain, water_level, along, clearance)))
+    }
+}
+
+/// Lateral of the highest ground within `search` of the `side` bank
+fn ridge_lateral(terrain: &TerrainSampler, along: f32, side: f32, search: f32) -> f32 {
+    let side = if side < 0.0 { -1.0 } else { 1.0 };
+    let half_width = terrain.river_width_at(along) * 0.5;
+    let centre = terrain.meander(along);
+    let steps = (search.max(0.0) / RIDGE_SEARCH_STEP).ceil() as usize;
+
+    (0..=steps)
+        .map(|i| side * (half_width + i as f32 * RIDGE_SEARCH_STEP))
+        .map(|lateral| (lateral, terrain.height(terrain.axis_point(along, centre + lateral))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(side * half_width, |(lateral, _)| lateral)
+}
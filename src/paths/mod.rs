@@ -0,0 +1,11 @@
+pub mod arc_length;
+pub mod curves;
+pub mod follower;
+pub mod frames;
+pub mod generators;
+
+pub use arc_length::*;
+pub use curves::*;
+pub use follower::*;
+pub use frames::*;
+pub use generators::*;
@@ -1,12 +1,13 @@
 use bevy::prelude::*;
 use crate::rendering::plane::Plane;
+use crate::game::{FlightSet, GameState};
 
 pub struct AnimationPlugin;
 
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PlaneAnimationState>()
-            .add_systems(Update, plane_swing_animation);
+            .add_systems(Update, plane_swing_animation.after(FlightSet).run_if(in_state(GameState::Playing)));
     }
 }
 
@@ -14,7 +15,6 @@ impl Plugin for AnimationPlugin {
 struct PlaneAnimationState {
     target_roll: f32,
     current_roll: f32,
-    initial_rotation: Option<Quat>,
 }
 
 fn plane_swing_animation(
@@ -30,22 +30,17 @@ fn plane_swing_animation(
 
     // Get the transform
     if let Ok(mut transform) = query.single_mut() {
-        // Store initial rotation if we haven't yet
-        if anim_state.initial_rotation.is_none() {
-            anim_state.initial_rotation = Some(transform.rotation);
-        }
-
         // Determine target roll based on input
-        if keyboard.pressed(KeyCode::ArrowLeft) {
+        if keyboard.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
             anim_state.target_roll = -MAX_ROLL;
-        } else if keyboard.pressed(KeyCode::ArrowRight) {
+        } else if keyboard.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
             anim_state.target_roll = MAX_ROLL;
         } else {
             anim_state.target_roll = 0.0;
         }
 
         // Smoothly interpolate current roll to target
-        let delta = time.elapsed().as_secs_f32();
+        let delta = time.delta_secs();
         let speed = if anim_state.target_roll == 0.0 { RETURN_SPEED } else { ROLL_SPEED };
         anim_state.current_roll = lerp(
             anim_state.current_roll,
@@ -53,10 +48,8 @@ fn plane_swing_animation(
             delta * speed
         );
 
-        // Apply roll rotation while preserving initial rotation
-        if let Some(initial_rot) = anim_state.initial_rotation {
-            transform.rotation = initial_rot * Quat::from_rotation_z(anim_state.current_roll);
-        }
+        // Roll on top of the heading the flight systems just set
+        transform.rotation *= Quat::from_rotation_z(anim_state.current_roll);
     }
 }
 
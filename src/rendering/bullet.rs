@@ -1,40 +1,108 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 
+use crate::game::{Collider, CollisionLayer, ContactDamage, DespawnOnHit, GameState, InRun, WeaponDef};
+
 pub struct BulletPlugin;
 
 #[derive(Component)]
 pub struct Bullet {
-    speed: f32,
+    velocity: Vec3,
+    /// Speed gained per second along the direction of travel
+    acceleration: f32,
+    lifetime: f32,
+}
+
+/// Mesh and material shared by every projectile of a weapon, so bullets batch
+/// instead of each bringing its own assets
+#[derive(Resource, Default)]
+pub struct BulletAssets {
+    looks: HashMap<AssetId<WeaponDef>, (Handle<Mesh>, Handle<StandardMaterial>)>,
+}
+
+impl BulletAssets {
+    /// Handles for projectiles of the weapon `id`, made on its first shot
+    pub fn look(
+        &mut self,
+        id: AssetId<WeaponDef>,
+        def: &WeaponDef,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> (Handle<Mesh>, Handle<StandardMaterial>) {
+        self.looks
+            .entry(id)
+            .or_insert_with(|| {
+                let [r, g, b] = def.color;
+                let material = materials.add(StandardMaterial {
+                    base_color: Color::srgb(r, g, b),
+                    emissive: LinearRgba::rgb(r, g, b) * 2.0,
+                    ..default()
+                });
+                (meshes.add(Sphere::new(def.radius)), material)
+            })
+            .clone()
+    }
 }
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (move_bullets, cleanup_bullets));
+        app.init_resource::<BulletAssets>()
+            .add_systems(Update, forget_changed_bullet_looks)
+            .add_systems(Update, (move_bullets, cleanup_bullets)
+                .run_if(in_state(GameState::Playing)));
+    }
+}
+
+/// Rebuilds a weapon's look the next time it fires after its definition is reloaded
+fn forget_changed_bullet_looks(mut events: EventReader<AssetEvent<WeaponDef>>, mut assets: ResMut<BulletAssets>) {
+    for event in events.read() {
+        if let AssetEvent::Modified { id } | AssetEvent::Removed { id } = event {
+            assets.looks.remove(id);
+        }
     }
 }
 
-fn move_bullets(mut bullets: Query<(&Bullet, &mut Transform)>, time: Res<Time>) {
-    for (bullet, mut transform) in bullets.iter_mut() {
-        // Move bullet forward (negative Z in our coordinate system)
-        transform.translation.z -= bullet.speed * time.delta().as_secs_f32();
+fn move_bullets(mut bullets: Query<(&mut Bullet, &mut Transform)>, time: Res<Time>) {
+    for (mut bullet, mut transform) in bullets.iter_mut() {
+        let boost = bullet.velocity.normalize_or_zero() * bullet.acceleration * time.delta_secs();
+        bullet.velocity += boost;
+        transform.translation += bullet.velocity * time.delta_secs();
+        bullet.lifetime -= time.delta_secs();
     }
 }
 
-fn cleanup_bullets(mut commands: Commands, bullets: Query<(Entity, &Transform), With<Bullet>>) {
-    // Remove bullets that have gone too far
-    for (entity, transform) in bullets.iter() {
-        if transform.translation.z < -100.0 {
+fn cleanup_bullets(mut commands: Commands, bullets: Query<(Entity, &Bullet)>) {
+    // Remove bullets that have flown for too long
+    for (entity, bullet) in bullets.iter() {
+        if bullet.lifetime <= 0.0 {
             commands.entity(entity).despawn();
         }
     }
 }
 
-// Function to spawn a bullet
-pub fn spawn_bullet(commands: &mut Commands, meshes: &mut Assets<Mesh>, materials: &mut Assets<StandardMaterial>, position: Vec3) {
+// Function to spawn one projectile of a weapon, already moving at `velocity`
+// with the mesh and material from `BulletAssets::look`
+pub fn spawn_bullet(
+    commands: &mut Commands,
+    (mesh, material): (Handle<Mesh>, Handle<StandardMaterial>),
+    def: &WeaponDef,
+    layer: CollisionLayer,
+    position: Vec3,
+    velocity: Vec3,
+) {
     commands.spawn((
-        Bullet { speed: 50.0 }, // Bullet speed
-        Mesh3d(meshes.add(Sphere::new(0.25))), // Bullet mesh (sphere)
-        MeshMaterial3d(materials.add(Color::srgb(1.0, 1.0, 0.0))), // Yellow color using sRGB values
+        Bullet {
+            velocity,
+            acceleration: def.acceleration,
+            lifetime: def.lifetime,
+        },
+        Collider::sphere(def.radius, layer),
+        ContactDamage(def.damage),
+        DespawnOnHit,
+        Mesh3d(mesh),
+        MeshMaterial3d(material),
         Transform::from_translation(position),
+        StateScoped(InRun),
     ));
-}
\ No newline at end of file
+}
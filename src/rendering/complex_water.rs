@@ -1,10 +1,16 @@
 use bevy::{
     ecs::query::QuerySingleError, pbr::{ExtendedMaterial, MaterialExtension}, prelude::*, reflect::Reflect, render::render_resource::{AsBindGroup, ShaderRef}
 };
+use bevy::asset::LoadedFolder;
 use bevy_egui::{egui, EguiContexts, EguiPrimaryContextPass};
 use bevy::prelude::AlphaMode;
 use rand::rand_core::le;
 
+use crate::game::EditorUiSet;
+use crate::heightmap_material::water_presets::{
+    water_preset_buttons, ApplyWaterPreset, WaterPreset, WaterPresetLibrary, WaterPresetPlugin,
+    WaterPresetTarget, WaterPresetTargetPlugin, WaterPresetValues,
+};
 use crate::rendering::caustic_floor_material::CompleteCausticFloorMaterial;
 
 /// This struct packs the custom shader data into Vec4 fields to ensure a stable
@@ -46,23 +52,48 @@ struct WaterConfigUI {
     pub roughness: f32,
     pub refraction_strength: f32,
 }
-impl WaterConfigUI {
-    pub fn apply_crystal_clear_preset(&mut self) {
-        self.water_clarity = 0.95;
-        self.reflectance = 0.9;
-        self.roughness = 0.02;
-        self.transparency = 0.9;
-        self.wave_amplitude = 0.1;
-        self.foam_intensity = 0.3;
+impl WaterPresetTarget for WaterConfigUI {
+    fn preset_values(&self) -> WaterPresetValues {
+        WaterPresetValues {
+            wave_amplitude: Some(self.wave_amplitude),
+            wave_frequency: Some(self.wave_frequency),
+            wave_speed: Some(self.wave_speed),
+            wave_steepness: Some(self.wave_steepness),
+            foam_intensity: Some(self.foam_intensity),
+            foam_cutoff: Some(self.foam_cutoff),
+            water_clarity: Some(self.water_clarity),
+            transparency: Some(self.transparency),
+            reflectance: Some(self.reflectance),
+            roughness: Some(self.roughness),
+            refraction_strength: Some(self.refraction_strength),
+            caustic_intensity: Some(self.caustic_intensity),
+            caustic_scale: Some(self.caustic_scale),
+            caustic_speed: Some(self.caustic_speed),
+            caustic_depth_fade: Some(self.caustic_depth_fade),
+        }
     }
-    
-    pub fn apply_shallow_lagoon_preset(&mut self) {
-        self.water_clarity = 0.98;
-        self.reflectance = 0.85;
-        self.roughness = 0.01;
-        self.transparency = 0.95;
-        self.wave_amplitude = 0.05;
-        self.foam_intensity = 0.1;
+
+    fn apply_preset_values(&mut self, values: &WaterPresetValues) {
+        let set = |target: &mut f32, value: Option<f32>| {
+            if let Some(value) = value {
+                *target = value;
+            }
+        };
+        set(&mut self.wave_amplitude, values.wave_amplitude);
+        set(&mut self.wave_frequency, values.wave_frequency);
+        set(&mut self.wave_speed, values.wave_speed);
+        set(&mut self.wave_steepness, values.wave_steepness);
+        set(&mut self.foam_intensity, values.foam_intensity);
+        set(&mut self.foam_cutoff, values.foam_cutoff);
+        set(&mut self.water_clarity, values.water_clarity);
+        set(&mut self.transparency, values.transparency);
+        set(&mut self.reflectance, values.reflectance);
+        set(&mut self.roughness, values.roughness);
+        set(&mut self.refraction_strength, values.refraction_strength);
+        set(&mut self.caustic_intensity, values.caustic_intensity);
+        set(&mut self.caustic_scale, values.caustic_scale);
+        set(&mut self.caustic_speed, values.caustic_speed);
+        set(&mut self.caustic_depth_fade, values.caustic_depth_fade);
     }
 }
 
@@ -117,6 +148,9 @@ pub struct ComplexWaterPlugin;
 
 impl Plugin for ComplexWaterPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<WaterPresetPlugin>() {
+            app.add_plugins(WaterPresetPlugin);
+        }
         app.add_plugins(MaterialPlugin::<CompleteComplexWaterMaterial>::default())
             .add_plugins(MaterialPlugin::<CompleteCausticFloorMaterial>::default()) // Add this
             .add_systems(Update, (
@@ -124,7 +158,8 @@ impl Plugin for ComplexWaterPlugin {
                 update_caustic_time, // Add this
             ))
             .init_resource::<WaterConfigUI>()
-            .add_systems(EguiPrimaryContextPass, water_ui_system)
+            .add_plugins(WaterPresetTargetPlugin::<WaterConfigUI>::default())
+            .add_systems(EguiPrimaryContextPass, water_ui_system.in_set(EditorUiSet))
             .add_systems(Update, (
                 update_all_water_materials,
                 update_all_caustic_materials,
@@ -146,6 +181,10 @@ pub fn update_water_time(
 fn water_ui_system(
     mut contexts: EguiContexts,
     mut config: ResMut<WaterConfigUI>,
+    mut library: Option<ResMut<WaterPresetLibrary>>,
+    folders: Res<Assets<LoadedFolder>>,
+    presets: Res<Assets<WaterPreset>>,
+    mut preset_requests: EventWriter<ApplyWaterPreset>,
 ) -> Result<(), BevyError> {
     // return fast when contexts.ctx_mut() is None
     let ctx = contexts.ctx_mut()?;
@@ -227,60 +266,10 @@ fn water_ui_system(
             
             // Preset buttons
             ui.heading("Presets");
-            ui.horizontal(|ui| {
-                if ui.button("Calm Lake").clicked() {
-                    config.wave_amplitude = 0.05;
-                    config.wave_frequency = 0.2;
-                    config.wave_speed = 0.3;
-                    config.wave_steepness = 2.0;
-                    config.foam_intensity = 0.5;
-                    config.foam_cutoff = 0.8;
-                    config.transparency = 0.8;
-                }
-                
-                if ui.button("Ocean Waves").clicked() {
-                    config.wave_amplitude = 0.3;
-                    config.wave_frequency = 0.15;
-                    config.wave_speed = 0.6;
-                    config.wave_steepness = 4.0;
-                    config.foam_intensity = 1.5;
-                    config.foam_cutoff = 0.6;
-                    config.transparency = 0.5;
-                }
-            });
-            
-            ui.horizontal(|ui| {
-                if ui.button("Fast Stream").clicked() {
-                    config.wave_amplitude = 0.08;
-                    config.wave_frequency = 0.8;
-                    config.wave_speed = 2.0;
-                    config.wave_steepness = 2.0;
-                    config.foam_intensity = 0.8;
-                    config.foam_cutoff = 0.7;
-                    config.transparency = 0.7;
-                }
-                
-                if ui.button("Rough Sea").clicked() {
-                    config.wave_amplitude = 0.4;
-                    config.wave_frequency = 0.12;
-                    config.wave_speed = 0.8;
-                    config.wave_steepness = 5.0;
-                    config.foam_intensity = 2.0;
-                    config.foam_cutoff = 0.5;
-                    config.transparency = 0.4;
-                }
-            });
+            if let Some(library) = library.as_deref_mut() {
+                water_preset_buttons(ui, library, &folders, &presets, &mut preset_requests);
+            }
 
-            ui.horizontal(|ui| {
-                if ui.button("Crystal Clear").clicked() {
-                    config.apply_crystal_clear_preset();
-                }
-                
-                if ui.button("Shallow Lagoon").clicked() {
-                    config.apply_shallow_lagoon_preset();
-                }
-            });
-            
             ui.separator();
             
             // Display current Vec4 values for debugging
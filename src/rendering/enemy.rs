@@ -1,24 +1,75 @@
 use bevy::prelude::*;
 
+use crate::game::{Collider, CollisionLayer, ContactDamage, EnemyArchetype, EnemyBody, EnemyKind, EnemyMotion, Health, InRun, MovementMode, Points, Steering, Velocity};
+use crate::heightmap_material::WaterWakeEmitter;
+
+/// Marks every enemy, whatever its archetype
 #[derive(Component)]
-pub struct Enemy {
-    pub speed: f32,
-}
+pub struct Enemy;
 
-// Helper function to spawn an enemy.
-pub fn spawn_enemy(
+/// Spawns an enemy of the given archetype. `EnemyPlugin` places it from `motion`
+/// on its first update and arms it once its weapon has loaded.
+pub fn spawn_archetype_enemy(
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>   
+    asset_server: &AssetServer,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    handle: Handle<EnemyArchetype>,
+    archetype: &EnemyArchetype,
+    motion: EnemyMotion,
 ) -> Entity {
-    // Load the enemy GLTF model
-    let model_scene_handle: Handle<Scene> = asset_server.load("models/enemy/enemy_plane.gltf#Scene0");
-    commands.spawn((
-        SceneRoot::from(model_scene_handle),
-        Transform::from_xyz(0.0, 2.0, -20.0) // Position in the middle of the scene
-            .with_scale(Vec3::new(3.3, 3.3, 3.3))
-            .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
-            Enemy {
-                speed: 15.0
-            },
-    )).id()
+    let mut enemy = commands.spawn((
+        Name::new(archetype.name.clone()),
+        Transform::default(),
+        Enemy,
+        EnemyKind(handle),
+        motion,
+        Velocity::default(),
+        Collider::sphere(archetype.radius, CollisionLayer::Enemy),
+        Health::new(archetype.health),
+        ContactDamage(100.0),
+        Points(archetype.points),
+        StateScoped(InRun),
+    ));
+
+    // Boats on the river churn up the water behind them
+    if matches!(archetype.movement, MovementMode::FollowRiver) {
+        enemy.insert(WaterWakeEmitter {
+            radius: archetype.radius,
+            ..default()
+        });
+    }
+
+    if let Some(steering) = &archetype.steering {
+        enemy.insert(Steering::new(steering.clone()));
+    }
+
+    match &archetype.model {
+        Some(model) => {
+            enemy.insert((
+                SceneRoot(asset_server.load(model.clone())),
+                Transform::from_scale(Vec3::splat(archetype.model_scale)),
+            ));
+        }
+        None => {
+            let mesh = match archetype.body {
+                EnemyBody::Box(size) => meshes.add(Cuboid::from_size(Vec3::from_array(size))),
+                EnemyBody::Sphere(radius) => meshes.add(Sphere::new(radius)),
+                // Lying down, nose along +Z
+                EnemyBody::Capsule { radius, length } => meshes.add(
+                    Capsule3d::new(radius, length)
+                        .mesh()
+                        .build()
+                        .rotated_by(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+                ),
+            };
+            let [r, g, b] = archetype.color;
+            enemy.insert((
+                Mesh3d(mesh),
+                MeshMaterial3d(materials.add(Color::srgb(r, g, b))),
+            ));
+        }
+    }
+
+    enemy.id()
 }
@@ -1,57 +1,63 @@
 use bevy::prelude::*;
-use crate::rendering::bullet::spawn_bullet;
-use crate::rendering::plane::Plane;
+use crate::game::{BombBay, BombSet, GameState, Weapon, WeaponLoadout, WeaponSet};
 
 pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (plane_movement_system, handle_shooting));
+        app.add_systems(Update, (switch_weapons, handle_shooting, handle_bombing)
+            .chain()
+            .before(WeaponSet)
+            .before(BombSet)
+            .run_if(in_state(GameState::Playing)));
     }
 }
 
-fn handle_shooting(
+const SLOT_KEYS: [KeyCode; 9] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+// Number keys pick a weapon slot, Tab cycles through them
+fn switch_weapons(
     keyboard: Res<ButtonInput<KeyCode>>,
-    query: Query<&Transform, With<Plane>>,
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(&mut Weapon, &mut WeaponLoadout)>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
-        if let Ok(plane_transform) = query.single() {
-            // Spawn bullet slightly below the plane
-            let bullet_pos = plane_transform.translation + Vec3::new(0.0, -1.0, 0.0);
-            spawn_bullet(&mut commands, &mut meshes, &mut materials, bullet_pos);
-        }
+    let Ok((mut weapon, mut loadout)) = query.single_mut() else {
+        return;
+    };
+    if keyboard.just_pressed(KeyCode::Tab) {
+        loadout.select_next(&mut weapon);
+    }
+    if let Some(index) = SLOT_KEYS.iter().position(|key| keyboard.just_pressed(*key)) {
+        loadout.select(&mut weapon, index);
     }
 }
 
-fn plane_movement_system(
+fn handle_shooting(
     keyboard: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(&Plane, &mut Transform)>,
-    time: Res<Time>,
+    mut query: Query<&mut Weapon, With<WeaponLoadout>>,
 ) {
-    for (plane, mut transform) in query.iter_mut() {
-        let mut movement = Vec3::ZERO;
-
-        // Forward/Backward movement
-        if keyboard.pressed(KeyCode::ArrowUp) {
-            movement.z -= 1.0;
-        }
-        if keyboard.pressed(KeyCode::ArrowDown) {
-            movement.z += 1.0;
-        }
-
-        // Left/Right movement
-        if keyboard.pressed(KeyCode::ArrowLeft) {
-            movement.x -= 1.0;
-        }
-        if keyboard.pressed(KeyCode::ArrowRight) {
-            movement.x += 1.0;
-        }
+    if let Ok(mut weapon) = query.single_mut() {
+        weapon.trigger_held = keyboard.pressed(KeyCode::Space);
+        weapon.trigger_pulled |= keyboard.just_pressed(KeyCode::Space);
+    }
+}
 
-        if movement != Vec3::ZERO {
-            transform.translation += movement.normalize() * plane.speed * time.delta().as_secs_f32();
+fn handle_bombing(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut query: Query<&mut BombBay>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyB) {
+        if let Ok(mut bay) = query.single_mut() {
+            bay.release = true;
         }
     }
-}
\ No newline at end of file
+}
@@ -4,8 +4,6 @@ pub mod animation;
 pub mod bullet;
 pub mod plane;
 pub mod enemy;
-pub mod spline;
-pub mod enemy_spline_follower;
 pub mod fbm_terrain;
 pub mod water;
 pub mod complex_water;
@@ -1,29 +1,43 @@
 use bevy::prelude::*;
 
+use crate::game::{Collider, CollisionLayer, ContactDamage, Health, InRun, PlayerFlight, Velocity};
+use crate::heightmap_material::WaterWakeEmitter;
+
 pub struct PlanePlugin;
 
 impl Plugin for PlanePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_plane);
+        app.add_systems(OnEnter(InRun), spawn_plane);
     }
 }
 
 #[derive(Component)]
-pub struct Plane {
-    pub speed: f32,
-}
+pub struct Plane;
 
 fn spawn_plane(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
 ) {
-    // Load and spawn the GLTF model - positioned at bottom center
+    spawn_player_plane(&mut commands, &asset_server, 0.0);
+}
+
+/// Spawns the player `along` units down the river; FlightPlugin places it over the water
+pub fn spawn_player_plane(commands: &mut Commands, asset_server: &AssetServer, along: f32) -> Entity {
     let model_scene = asset_server.load("models/plane.gltf#Scene0");
     commands.spawn((
         SceneRoot::from(model_scene),
-        Transform::from_xyz(0.0, 6.0, -5.0)
-            .with_scale(Vec3::new(3.3, 3.3, 3.3))
-            .with_rotation(Quat::from_rotation_y(-std::f32::consts::PI)),
-        Plane { speed: 20.0 },
-    ));
+        Transform::from_scale(Vec3::new(3.3, 3.3, 3.3)),
+        Plane,
+        PlayerFlight {
+            along,
+            ..default()
+        },
+        Velocity::default(),
+        Collider::sphere(3.0, CollisionLayer::Player),
+        Health::new(1.0),
+        ContactDamage(100.0),
+        // Low passes over the river leave a wake
+        WaterWakeEmitter::default(),
+        StateScoped(InRun),
+    )).id()
 }
\ No newline at end of file